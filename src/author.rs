@@ -0,0 +1,114 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// An owned commit author or committer identity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    /// Name of the person
+    pub name: String,
+    /// Email of the person
+    pub email: String,
+    /// When the signature was made
+    pub time: SystemTime,
+}
+
+impl Author {
+    /// Create an author identity signed at the current time
+    pub fn now(name: impl Into<String>, email: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            email: email.into(),
+            time: SystemTime::now(),
+        }
+    }
+}
+
+impl From<&git2::Signature<'_>> for Author {
+    fn from(signature: &git2::Signature<'_>) -> Self {
+        Self {
+            name: String::from_utf8_lossy(signature.name_bytes()).into_owned(),
+            email: String::from_utf8_lossy(signature.email_bytes()).into_owned(),
+            time: system_time(signature.when()),
+        }
+    }
+}
+
+impl From<git2::Signature<'_>> for Author {
+    fn from(signature: git2::Signature<'_>) -> Self {
+        Self::from(&signature)
+    }
+}
+
+impl TryFrom<&Author> for git2::Signature<'static> {
+    type Error = git2::Error;
+
+    /// Convert to a git2 signature in UTC
+    fn try_from(author: &Author) -> Result<Self, Self::Error> {
+        let seconds = match author.time.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => since_epoch.as_secs() as i64,
+            Err(before_epoch) => -(before_epoch.duration().as_secs() as i64),
+        };
+
+        git2::Signature::new(&author.name, &author.email, &git2::Time::new(seconds, 0))
+    }
+}
+
+/// Name and email commits are signed with when neither the caller nor git config provide one
+const FALLBACK_NAME: &str = "git-ops";
+const FALLBACK_EMAIL: &str = "git-ops@localhost";
+
+/// Signature for commits git-ops creates, signed at the current time
+///
+/// Uses `configured` if set, else `user.name` and `user.email` from git config, else a
+/// `git-ops <git-ops@localhost>` placeholder so a machine without an identity can still merge.
+pub(crate) fn commit_signature(
+    repo: &git2::Repository,
+    configured: Option<&Author>,
+) -> Result<git2::Signature<'static>, git2::Error> {
+    match configured {
+        Some(author) => git2::Signature::now(&author.name, &author.email),
+        None => repo
+            .signature()
+            .or_else(|_| git2::Signature::now(FALLBACK_NAME, FALLBACK_EMAIL)),
+    }
+}
+
+/// Convert a git2 timestamp into a `SystemTime`
+pub(crate) fn system_time(time: git2::Time) -> SystemTime {
+    let seconds = time.seconds();
+    if seconds >= 0 {
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_author_from_signature() {
+        let signature =
+            git2::Signature::new("Test User", "test@example.com", &git2::Time::new(1_000, 60))
+                .unwrap();
+
+        let author = Author::from(&signature);
+
+        assert_eq!(author.name, "Test User");
+        assert_eq!(author.email, "test@example.com");
+        assert_eq!(author.time, UNIX_EPOCH + Duration::from_secs(1_000));
+    }
+
+    #[test]
+    fn test_author_round_trip() {
+        let author = Author {
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            time: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+
+        let signature = git2::Signature::try_from(&author).unwrap();
+
+        assert_eq!(Author::from(&signature), author);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+/// Concurrency limits for operations that run across many repositories
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Maximum number of repositories processed at once
+    pub max_concurrent: usize,
+    /// Maximum number of simultaneous connections to a single host; `None` for no limit
+    pub max_per_host: Option<usize>,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 8,
+            max_per_host: None,
+        }
+    }
+}
+
+/// Work shared between batch workers
+struct Schedule {
+    /// Indices of the items not yet started
+    pending: VecDeque<usize>,
+    /// Number of in-flight items per host
+    active_per_host: HashMap<String, usize>,
+}
+
+/// Run `operation` for each repository, honoring the global and per-host limits
+///
+/// `hosts[i]` is the host `repo_paths[i]` connects to, or `None` for local remotes, which only
+/// count towards the global limit. Results are returned in the same order as `repo_paths`.
+pub(crate) fn run<T, F>(
+    repo_paths: &[&Path],
+    hosts: &[Option<String>],
+    options: &BatchOptions,
+    operation: F,
+) -> Vec<(PathBuf, T)>
+where
+    T: Send,
+    F: Fn(&Path) -> T + Sync,
+{
+    let schedule = Mutex::new(Schedule {
+        pending: (0..repo_paths.len()).collect(),
+        active_per_host: HashMap::new(),
+    });
+    let host_released = Condvar::new();
+    let per_host_limit = options.max_per_host.unwrap_or(usize::MAX).max(1);
+    let worker_count = options.max_concurrent.clamp(1, repo_paths.len().max(1));
+
+    let mut results: Vec<Option<T>> = (0..repo_paths.len()).map(|_| None).collect();
+
+    let finished: Vec<(usize, T)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..worker_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut finished = Vec::new();
+                    while let Some(index) =
+                        next_item(&schedule, &host_released, hosts, per_host_limit)
+                    {
+                        finished.push((index, operation(repo_paths[index])));
+
+                        if let Some(host) = &hosts[index] {
+                            let mut schedule = schedule.lock().unwrap();
+                            if let Some(active) = schedule.active_per_host.get_mut(host) {
+                                *active -= 1;
+                            }
+                            host_released.notify_all();
+                        }
+                    }
+                    finished
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    for (index, result) in finished {
+        results[index] = Some(result);
+    }
+
+    repo_paths
+        .iter()
+        .zip(results)
+        .map(|(repo_path, result)| (repo_path.to_path_buf(), result.unwrap()))
+        .collect()
+}
+
+/// Claim the next pending item whose host has a free slot, waiting if every host is saturated
+fn next_item(
+    schedule: &Mutex<Schedule>,
+    host_released: &Condvar,
+    hosts: &[Option<String>],
+    per_host_limit: usize,
+) -> Option<usize> {
+    let mut schedule = schedule.lock().unwrap();
+    loop {
+        if schedule.pending.is_empty() {
+            return None;
+        }
+
+        let position = schedule
+            .pending
+            .iter()
+            .position(|&index| match &hosts[index] {
+                Some(host) => {
+                    schedule.active_per_host.get(host).copied().unwrap_or(0) < per_host_limit
+                }
+                None => true,
+            });
+
+        if let Some(position) = position {
+            let index = schedule.pending.remove(position)?;
+            if let Some(host) = &hosts[index] {
+                *schedule.active_per_host.entry(host.clone()).or_insert(0) += 1;
+            }
+            return Some(index);
+        }
+
+        schedule = host_released.wait(schedule).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_respects_per_host_limit() {
+        let paths: Vec<PathBuf> = (0..6).map(|i| PathBuf::from(format!("repo-{i}"))).collect();
+        let repo_paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        let hosts = vec![Some("github.com".to_string()); 6];
+        let options = BatchOptions {
+            max_concurrent: 6,
+            max_per_host: Some(2),
+        };
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let results = run(&repo_paths, &hosts, &options, |repo_path| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            active.fetch_sub(1, Ordering::SeqCst);
+            repo_path.to_path_buf()
+        });
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert!(results.iter().all(|(path, result)| path == result));
+        assert_eq!(results[5].0, PathBuf::from("repo-5"));
+    }
+
+    #[test]
+    fn test_run_distinct_hosts_in_parallel() {
+        let paths: Vec<PathBuf> = (0..4).map(|i| PathBuf::from(format!("repo-{i}"))).collect();
+        let repo_paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        let hosts: Vec<Option<String>> = (0..4).map(|i| Some(format!("host-{i}"))).collect();
+        let options = BatchOptions {
+            max_concurrent: 4,
+            max_per_host: Some(1),
+        };
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        run(&repo_paths, &hosts, &options, |_| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            active.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(peak.load(Ordering::SeqCst) > 1);
+    }
+}
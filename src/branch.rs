@@ -0,0 +1,296 @@
+use git2::{BranchType, ErrorCode, Repository};
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// Which branches to list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchFilter {
+    /// Branches under `refs/heads`
+    Local,
+    /// Remote-tracking branches under `refs/remotes`
+    Remote,
+    /// Local branches followed by remote-tracking branches
+    All,
+}
+
+/// A branch and the commit it points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchInfo {
+    /// Short name, e.g. `main` or `origin/main`
+    pub name: String,
+    /// Whether this is a remote-tracking branch
+    pub is_remote: bool,
+    /// Whether HEAD points at this branch
+    pub is_head: bool,
+    /// Id of the commit the branch points at, as hex
+    pub target: String,
+}
+
+/// Branch operations for Git repositories
+pub struct GitBranch;
+
+impl GitBranch {
+    /// List branches, sorted by name within local and remote-tracking branches
+    ///
+    /// Symbolic branches such as `origin/HEAD` are listed with the commit they resolve to.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `filter` - Which kinds of branches to list
+    pub fn list_branches(
+        repo_path: &Path,
+        filter: BranchFilter,
+    ) -> Result<Vec<BranchInfo>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let branch_type = match filter {
+            BranchFilter::Local => Some(BranchType::Local),
+            BranchFilter::Remote => Some(BranchType::Remote),
+            BranchFilter::All => None,
+        };
+
+        let mut branches = Vec::new();
+        for branch in repo.branches(branch_type)? {
+            let (branch, branch_type) = branch?;
+            let Some(name) = branch.name()? else {
+                continue;
+            };
+            let Some(target) = branch.get().resolve()?.target() else {
+                continue;
+            };
+
+            branches.push(BranchInfo {
+                name: name.to_string(),
+                is_remote: branch_type == BranchType::Remote,
+                is_head: branch.is_head(),
+                target: target.to_string(),
+            });
+        }
+
+        Ok(branches)
+    }
+
+    /// Create a local branch without checking it out, like `git branch`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Name of the new branch, e.g. `feature`
+    /// * `start_point` - Revision the branch starts at (branch, tag, SHA, `HEAD~1`, ...), HEAD if `None`
+    /// * `force` - Move the branch if it already exists instead of failing
+    pub fn create_branch(
+        repo_path: &Path,
+        name: &str,
+        start_point: Option<&str>,
+        force: bool,
+    ) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let start_commit = repo
+            .revparse_single(start_point.unwrap_or("HEAD"))?
+            .peel_to_commit()?;
+        repo.branch(name, &start_commit, force)?;
+        Ok(())
+    }
+
+    /// Delete a local branch, like `git branch -d`
+    ///
+    /// A branch counts as merged when its tip is reachable from its upstream branch, or from
+    /// HEAD if it has no upstream.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch, e.g. `feature`
+    /// * `force` - Delete the branch even if it isn't merged, like `git branch -D`
+    ///
+    /// # Errors
+    /// Returns `BranchInUse` if the branch is checked out, and `BranchNotMerged` if it isn't
+    /// merged and `force` is false.
+    pub fn delete_branch(repo_path: &Path, branch_name: &str, force: bool) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+        if branch.is_head() {
+            return Err(GitError::BranchInUse {
+                branch: branch_name.to_string(),
+                path: repo_path.to_path_buf(),
+            });
+        }
+
+        if !force {
+            let tip = branch.get().peel_to_commit()?.id();
+            let merged_into = match branch.upstream() {
+                Ok(upstream) => Some(upstream.get().peel_to_commit()?.id()),
+                Err(e) if e.code() == ErrorCode::NotFound => repo
+                    .head()
+                    .ok()
+                    .and_then(|head| head.peel_to_commit().ok())
+                    .map(|commit| commit.id()),
+                Err(e) => return Err(GitError::Git(e)),
+            };
+
+            let merged = match merged_into {
+                Some(merged_into) => {
+                    merged_into == tip || repo.graph_descendant_of(merged_into, tip)?
+                }
+                None => false,
+            };
+            if !merged {
+                return Err(GitError::BranchNotMerged {
+                    branch: branch_name.to_string(),
+                    path: repo_path.to_path_buf(),
+                });
+            }
+        }
+
+        branch.delete()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo, upstream_and_clone};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_list_branches() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_upstream, local) = upstream_and_clone(temp_dir.path());
+        let base = commit_file(&local, "file.txt", "local\n", "Local change");
+        let head_commit = local.find_commit(base).unwrap();
+        local.branch("feature", &head_commit, false).unwrap();
+        let origin_main = local.refname_to_id("refs/remotes/origin/main").unwrap();
+
+        let local_branches =
+            GitBranch::list_branches(local.workdir().unwrap(), BranchFilter::Local).unwrap();
+        let remote_branches =
+            GitBranch::list_branches(local.workdir().unwrap(), BranchFilter::Remote).unwrap();
+        let all_branches =
+            GitBranch::list_branches(local.workdir().unwrap(), BranchFilter::All).unwrap();
+
+        assert_eq!(
+            local_branches,
+            vec![
+                BranchInfo {
+                    name: "feature".to_string(),
+                    is_remote: false,
+                    is_head: false,
+                    target: base.to_string(),
+                },
+                BranchInfo {
+                    name: "main".to_string(),
+                    is_remote: false,
+                    is_head: true,
+                    target: base.to_string(),
+                },
+            ]
+        );
+        assert!(remote_branches.contains(&BranchInfo {
+            name: "origin/main".to_string(),
+            is_remote: true,
+            is_head: false,
+            target: origin_main.to_string(),
+        }));
+        assert!(remote_branches.iter().all(|branch| branch.is_remote));
+        assert_eq!(
+            all_branches.len(),
+            local_branches.len() + remote_branches.len()
+        );
+    }
+
+    #[test]
+    fn test_create_branch_at_revspec() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        let second = commit_file(&repo, "file.txt", "two\n", "Second");
+
+        GitBranch::create_branch(temp_dir.path(), "previous", Some("HEAD~1"), false).unwrap();
+        GitBranch::create_branch(temp_dir.path(), "current", None, false).unwrap();
+
+        let target = |name: &str| repo.refname_to_id(&format!("refs/heads/{name}")).unwrap();
+        assert_eq!(target("previous"), first);
+        assert_eq!(target("current"), second);
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/main"));
+    }
+
+    #[test]
+    fn test_create_existing_branch_needs_force() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        commit_file(&repo, "file.txt", "two\n", "Second");
+        GitBranch::create_branch(temp_dir.path(), "feature", None, false).unwrap();
+
+        let without_force =
+            GitBranch::create_branch(temp_dir.path(), "feature", Some("HEAD~1"), false);
+        let with_force = GitBranch::create_branch(temp_dir.path(), "feature", Some("HEAD~1"), true);
+
+        assert!(without_force.is_err());
+        assert!(with_force.is_ok());
+        assert_eq!(repo.refname_to_id("refs/heads/feature").unwrap(), first);
+    }
+
+    #[test]
+    fn test_delete_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("merged", &head, false).unwrap();
+
+        GitBranch::delete_branch(temp_dir.path(), "merged", false).unwrap();
+
+        assert!(repo.find_branch("merged", BranchType::Local).is_err());
+    }
+
+    #[test]
+    fn test_delete_branch_refuses_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let result = GitBranch::delete_branch(temp_dir.path(), "main", true);
+
+        assert!(matches!(
+            result,
+            Err(GitError::BranchInUse { branch, .. }) if branch == "main"
+        ));
+        assert!(repo.find_branch("main", BranchType::Local).is_ok());
+    }
+
+    #[test]
+    fn test_delete_unmerged_branch_needs_force() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        commit_file(&repo, "feature.txt", "feature\n", "Feature work");
+        repo.set_head("refs/heads/main").unwrap();
+
+        let without_force = GitBranch::delete_branch(temp_dir.path(), "feature", false);
+        let still_exists = repo.find_branch("feature", BranchType::Local).is_ok();
+        let with_force = GitBranch::delete_branch(temp_dir.path(), "feature", true);
+
+        assert!(matches!(
+            without_force,
+            Err(GitError::BranchNotMerged { .. })
+        ));
+        assert!(still_exists);
+        assert!(with_force.is_ok());
+        assert!(repo.find_branch("feature", BranchType::Local).is_err());
+    }
+}
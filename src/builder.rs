@@ -0,0 +1,148 @@
+use crate::auth::SshConfig;
+use crate::author::Author;
+use crate::client::GitClient;
+use crate::error::GitError;
+use crate::pull::MergeStrategy;
+use crate::retry::RetryPolicy;
+
+/// Configures a `GitClient` before creating it
+///
+/// Settings left unset keep the defaults of `GitClient::new`. Everything set here can also
+/// be changed later with the client's setters.
+#[derive(Debug, Clone, Default)]
+pub struct GitClientBuilder {
+    ssh_config: Option<SshConfig>,
+    remote_name: Option<String>,
+    merge_strategy: MergeStrategy,
+    retry_policy: RetryPolicy,
+    proxy: Option<String>,
+    signature: Option<Author>,
+}
+
+impl GitClientBuilder {
+    /// Create a builder with every setting at its default
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Authenticate SSH remotes with `ssh_config` instead of `SshConfig::from_environment`
+    pub fn ssh_config(mut self, ssh_config: SshConfig) -> Self {
+        self.ssh_config = Some(ssh_config);
+        self
+    }
+
+    /// Operate on `remote_name` instead of `origin`, see `GitClient::set_remote_name`
+    pub fn remote_name(mut self, remote_name: impl Into<String>) -> Self {
+        self.remote_name = Some(remote_name.into());
+        self
+    }
+
+    /// Reconcile diverged branches in `pull` with `merge_strategy`, see `GitClient::set_merge_strategy`
+    pub fn merge_strategy(mut self, merge_strategy: MergeStrategy) -> Self {
+        self.merge_strategy = merge_strategy;
+        self
+    }
+
+    /// Retry transient network failures under `retry_policy`, see `GitClient::set_retry_policy`
+    pub fn retry(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Connect to HTTP(S) remotes through `proxy`, see `GitClient::set_proxy`
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Sign merge and rebased commits as `signature`, see `GitClient::set_signature`
+    pub fn signature(mut self, signature: Author) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Create the configured client
+    ///
+    /// # Errors
+    /// Without an explicit `ssh_config`, fails like `SshConfig::from_environment` when the
+    /// home directory can't be found
+    pub fn build(self) -> Result<GitClient, GitError> {
+        let ssh_config = match self.ssh_config {
+            Some(ssh_config) => ssh_config,
+            None => SshConfig::from_environment()?,
+        };
+
+        let mut client = GitClient::with_ssh_config(ssh_config);
+        if let Some(remote_name) = self.remote_name {
+            client.set_remote_name(remote_name);
+        }
+        client.set_merge_strategy(self.merge_strategy);
+        client.set_retry_policy(self.retry_policy);
+        client.set_proxy(self.proxy);
+        client.set_signature(self.signature);
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pull::PullOutcome;
+    use crate::test_support::{commit_file, upstream_and_clone};
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_customized_client() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        local.remote_rename("origin", "upstream").unwrap();
+        let remote_commit = commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local change");
+        let retry_policy = RetryPolicy::new(3, Duration::from_millis(10));
+
+        let client = GitClientBuilder::new()
+            .ssh_config(SshConfig::new(
+                vec![],
+                PathBuf::from("/nonexistent/known_hosts"),
+                false,
+            ))
+            .remote_name("upstream")
+            .merge_strategy(MergeStrategy::Merge)
+            .retry(retry_policy)
+            .proxy("http://proxy.example.com:8080")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.remote_name(), "upstream");
+        assert_eq!(client.merge_strategy(), MergeStrategy::Merge);
+        assert_eq!(client.retry_policy(), retry_policy);
+        assert_eq!(client.proxy(), Some("http://proxy.example.com:8080"));
+
+        let outcome = client.pull_detailed(local.workdir().unwrap()).unwrap();
+
+        let PullOutcome::Merged { commit } = outcome else {
+            panic!("expected a merge commit, got {outcome:?}");
+        };
+        let parents: Vec<_> = local.find_commit(commit).unwrap().parent_ids().collect();
+        assert_eq!(parents, vec![local_commit, remote_commit]);
+    }
+
+    #[test]
+    fn test_build_defaults() {
+        let client = GitClientBuilder::new()
+            .ssh_config(SshConfig::new(
+                vec![],
+                PathBuf::from("/nonexistent/known_hosts"),
+                false,
+            ))
+            .build()
+            .unwrap();
+
+        assert_eq!(client.remote_name(), "origin");
+        assert_eq!(client.merge_strategy(), MergeStrategy::FastForwardOnly);
+        assert_eq!(client.retry_policy(), RetryPolicy::default());
+        assert_eq!(client.proxy(), None);
+    }
+}
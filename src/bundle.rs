@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use crate::command;
+use crate::error::GitError;
+
+/// Refspecs used when fetching from a bundle
+const BUNDLE_REFSPECS: [&str; 2] = [
+    "+refs/heads/*:refs/remotes/bundle/*",
+    "refs/tags/*:refs/tags/*",
+];
+
+/// Bundle operations for moving history without a network connection
+///
+/// libgit2 has no bundle support, so these shell out to `git bundle` / `git fetch`.
+pub struct GitBundle;
+
+impl GitBundle {
+    /// Fetch the objects and refs from a bundle file into the repository
+    ///
+    /// Branches in the bundle are stored as `refs/remotes/bundle/<branch>` and tags are
+    /// fetched as-is (existing tags are never overwritten).
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `bundle` - Path to the bundle file
+    pub fn fetch_from_bundle(repo_path: &Path, bundle: &Path) -> Result<(), GitError> {
+        let bundle = Self::absolute(repo_path, bundle)?;
+        let bundle = bundle.to_string_lossy();
+
+        let mut args = vec!["fetch", "--quiet", bundle.as_ref()];
+        args.extend(BUNDLE_REFSPECS);
+        command::run_git_checked(repo_path, &args)?;
+
+        Ok(())
+    }
+
+    /// Write a bundle containing `refs` (or all refs when empty) to `dest`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `refs` - Refs or revision ranges to include, e.g. `main` or `v1.0..main`
+    /// * `dest` - Path of the bundle file to create
+    pub fn create_bundle(repo_path: &Path, refs: &[&str], dest: &Path) -> Result<(), GitError> {
+        let dest = Self::absolute(repo_path, dest)?;
+        let dest = dest.to_string_lossy();
+
+        let mut args = vec!["bundle", "create", "--quiet", dest.as_ref()];
+        if refs.is_empty() {
+            args.push("--all");
+        } else {
+            args.extend(refs);
+        }
+        command::run_git_checked(repo_path, &args)?;
+
+        Ok(())
+    }
+
+    /// Resolve `path` against the caller's working directory, since `git -C` changes it
+    fn absolute(repo_path: &Path, path: &Path) -> Result<std::path::PathBuf, GitError> {
+        std::path::absolute(path).map_err(|e| GitError::CommandFailed {
+            command: "git bundle".to_string(),
+            path: repo_path.to_path_buf(),
+            stderr: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_path = temp_dir.path().join("source");
+        let source = init_repo(&source_path);
+        let commit = commit_file(&source, "file.txt", "one\n", "Initial commit");
+        let head_commit = source.find_commit(commit).unwrap();
+        source
+            .tag_lightweight("v1.0", head_commit.as_object(), false)
+            .unwrap();
+        let bundle = temp_dir.path().join("repo.bundle");
+        let dest_path = temp_dir.path().join("dest");
+        let dest = init_repo(&dest_path);
+
+        GitBundle::create_bundle(&source_path, &[], &bundle).unwrap();
+        GitBundle::fetch_from_bundle(&dest_path, &bundle).unwrap();
+
+        let fetched = dest.find_reference("refs/remotes/bundle/main").unwrap();
+        assert_eq!(fetched.target(), Some(commit));
+        assert!(dest.find_reference("refs/tags/v1.0").is_ok());
+    }
+
+    #[test]
+    fn test_create_bundle_unknown_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let result = GitBundle::create_bundle(
+            temp_dir.path(),
+            &["does-not-exist"],
+            &temp_dir.path().join("repo.bundle"),
+        );
+
+        assert!(matches!(result, Err(GitError::CommandFailed { .. })));
+    }
+}
@@ -1,21 +1,232 @@
-use git2::Repository;
-use std::path::Path;
+use git2::build::CheckoutBuilder;
+use git2::{CheckoutNotificationType, Delta, ErrorCode, Oid, Repository};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use crate::error::GitError;
+use crate::verify::GitVerify;
+
+/// How a checkout would change a working tree file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckoutChangeKind {
+    /// The file doesn't exist yet and would be created
+    Added,
+    /// The file would be rewritten with the target's contents
+    Modified,
+    /// The file would be removed
+    Deleted,
+    /// The file has local changes that the checkout would discard
+    LocalChangesLost,
+}
+
+/// A working tree file a checkout would touch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckoutChange {
+    /// Path relative to the repository root
+    pub path: PathBuf,
+    /// What would happen to the file
+    pub kind: CheckoutChangeKind,
+}
+
+/// Where `checkout_branch` left HEAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckoutTarget {
+    /// HEAD is on the local branch
+    Branch,
+    /// HEAD is detached at the remote-tracking branch's tip, see `CheckoutOptions::create_local`
+    DetachedAtRemote { commit: Oid },
+    /// The name was a tag, so HEAD is detached at the commit it points to
+    DetachedAtTag { commit: Oid },
+}
+
+/// Why a checkout told a `CheckoutNotifier` about a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckoutNotification {
+    /// The file has local changes in the way of the checkout
+    Conflict,
+    /// The file has local changes the checkout leaves alone
+    Dirty,
+    /// The file was written with the target's contents
+    Updated,
+    /// The file isn't tracked by the index
+    Untracked,
+}
+
+/// Type alias for a function told about the files a checkout touches
+type CheckoutNotifyFn = dyn Fn(CheckoutNotification, &Path) + Send + Sync;
+
+/// Called with each conflicting, dirty, updated or untracked file a checkout comes across
+///
+/// Two notifiers are equal only if they are clones of each other.
+#[derive(Clone)]
+pub struct CheckoutNotifier(Arc<CheckoutNotifyFn>);
+
+impl CheckoutNotifier {
+    /// Call `notify` with each file the checkout reports and why
+    pub fn new<F>(notify: F) -> Self
+    where
+        F: Fn(CheckoutNotification, &Path) + Send + Sync + 'static,
+    {
+        Self(Arc::new(notify))
+    }
+
+    /// Report files to this notifier from the checkout `builder` runs
+    pub(crate) fn apply(&self, builder: &mut CheckoutBuilder) {
+        let notify = self.0.clone();
+        builder.notify_on(
+            CheckoutNotificationType::CONFLICT
+                | CheckoutNotificationType::DIRTY
+                | CheckoutNotificationType::UPDATED
+                | CheckoutNotificationType::UNTRACKED,
+        );
+        builder.notify(move |kind, path, _baseline, _target, _workdir| {
+            let notification = if kind.contains(CheckoutNotificationType::CONFLICT) {
+                CheckoutNotification::Conflict
+            } else if kind.contains(CheckoutNotificationType::DIRTY) {
+                CheckoutNotification::Dirty
+            } else if kind.contains(CheckoutNotificationType::UPDATED) {
+                CheckoutNotification::Updated
+            } else {
+                CheckoutNotification::Untracked
+            };
+            if let Some(path) = path {
+                notify(notification, path);
+            }
+            true
+        });
+    }
+}
+
+impl fmt::Debug for CheckoutNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CheckoutNotifier")
+    }
+}
+
+impl PartialEq for CheckoutNotifier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CheckoutNotifier {}
+
+/// Options for checking out a branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckoutOptions {
+    /// Create a local branch when only the remote-tracking branch exists
+    ///
+    /// When `false` the remote-tracking branch's tip is checked out as a detached HEAD instead.
+    pub create_local: bool,
+    /// Make a branch created from the remote-tracking branch track it, like `git checkout`
+    ///
+    /// Writes `branch.<name>.remote` and `branch.<name>.merge`, so `git pull` and `git status`
+    /// know the branch's upstream.
+    pub track: bool,
+    /// Only update working tree files matching these pathspecs; empty updates every file
+    ///
+    /// HEAD still moves to the target, so files left out keep their old contents and show
+    /// up as local changes.
+    pub paths: Vec<String>,
+    /// Told about each file the checkout updates or finds in its way
+    pub notify: Option<CheckoutNotifier>,
+}
+
+impl Default for CheckoutOptions {
+    fn default() -> Self {
+        Self {
+            create_local: true,
+            track: true,
+            paths: Vec::new(),
+            notify: None,
+        }
+    }
+}
+
+impl CheckoutOptions {
+    /// A forced checkout builder restricted to `paths` and reporting to `notify`
+    fn builder(&self) -> CheckoutBuilder<'_> {
+        let mut builder = CheckoutBuilder::new();
+        builder.force();
+        for path in &self.paths {
+            builder.path(path);
+        }
+        if let Some(notify) = &self.notify {
+            notify.apply(&mut builder);
+        }
+        builder
+    }
+}
+
+/// How conflicts are written into working tree files, overriding `merge.conflictStyle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStyle {
+    /// Markers around our and their versions, git's default
+    Merge,
+    /// Markers around our and their versions plus the common ancestor's in a `|||||||` section
+    Diff3,
+}
+
+impl ConflictStyle {
+    /// Write conflicts in this style when `builder` checks out conflicted files
+    pub(crate) fn apply(self, builder: &mut CheckoutBuilder) {
+        match self {
+            ConflictStyle::Merge => builder.conflict_style_merge(true),
+            ConflictStyle::Diff3 => builder.conflict_style_diff3(true),
+        };
+    }
+}
 
 /// Checkout operations for Git repositories
 pub struct GitCheckout;
 
+/// Configure local `branch_name` to track the same-named branch of the remote `tracking_prefix` belongs to
+///
+/// The config is written directly rather than through `Branch::set_upstream`, which needs the
+/// remote's fetch refspec to map onto `tracking_prefix` and so rejects namespaced prefixes.
+fn set_upstream(
+    repo: &Repository,
+    branch_name: &str,
+    tracking_prefix: &str,
+) -> Result<(), git2::Error> {
+    let Some((_, remote_name)) = tracking_prefix.rsplit_once("refs/remotes/") else {
+        return Ok(());
+    };
+
+    let mut config = repo.config()?;
+    config.set_str(&format!("branch.{branch_name}.remote"), remote_name)?;
+    config.set_str(
+        &format!("branch.{branch_name}.merge"),
+        &format!("refs/heads/{branch_name}"),
+    )
+}
+
 impl GitCheckout {
     /// Checkout a branch in the repository
     ///
+    /// The name is looked up as a local branch, then as a remote-tracking branch, and finally
+    /// as a tag, which is checked out as a detached HEAD at the tagged commit.
+    ///
     /// # Arguments
     /// * `repo_path` - Path to the repository
-    /// * `branch_name` - Name of the branch to checkout
+    /// * `branch_name` - Name of the branch or tag to checkout
+    /// * `tracking_prefix` - Ref prefix the remote's branches are tracked under (e.g. `refs/remotes/origin`)
+    /// * `options` - Whether a missing local branch is created from the remote-tracking
+    ///   branch, which files are updated and who is told about them
+    ///
+    /// # Returns
+    /// Whether HEAD is now on a branch or detached
     ///
     /// # Errors
-    /// Returns an error if the branch doesn't exist or checkout fails
-    pub fn checkout_branch(repo_path: &Path, branch_name: &str) -> Result<(), GitError> {
+    /// Returns an error if neither a branch nor a tag has the name, or checkout fails
+    pub fn checkout_branch(
+        repo_path: &Path,
+        branch_name: &str,
+        tracking_prefix: &str,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutTarget, GitError> {
         let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
             path: repo_path.to_path_buf(),
             source: e,
@@ -23,7 +234,7 @@ impl GitCheckout {
 
         // Try to find the branch as a local branch first
         let branch_ref = format!("refs/heads/{}", branch_name);
-        let remote_branch_ref = format!("refs/remotes/origin/{}", branch_name);
+        let remote_branch_ref = format!("{}/{}", tracking_prefix, branch_name);
 
         // Check if local branch exists
         let reference = if repo.find_reference(&branch_ref).is_ok() {
@@ -44,6 +255,25 @@ impl GitCheckout {
                         source: e,
                     })?;
 
+            if !options.create_local {
+                repo.set_head_detached(remote_commit.id()).map_err(|e| {
+                    GitError::CheckoutFailed {
+                        branch: branch_name.to_string(),
+                        path: repo_path.to_path_buf(),
+                        source: e,
+                    }
+                })?;
+                repo.checkout_head(Some(&mut options.builder()))
+                    .map_err(|e| GitError::CheckoutFailed {
+                        branch: branch_name.to_string(),
+                        path: repo_path.to_path_buf(),
+                        source: e,
+                    })?;
+                return Ok(CheckoutTarget::DetachedAtRemote {
+                    commit: remote_commit.id(),
+                });
+            }
+
             repo.branch(branch_name, &remote_commit, false)
                 .map_err(|e| GitError::CheckoutFailed {
                     branch: branch_name.to_string(),
@@ -51,30 +281,53 @@ impl GitCheckout {
                     source: e,
                 })?;
 
+            if options.track {
+                set_upstream(&repo, branch_name, tracking_prefix).map_err(|e| {
+                    GitError::CheckoutFailed {
+                        branch: branch_name.to_string(),
+                        path: repo_path.to_path_buf(),
+                        source: e,
+                    }
+                })?;
+            }
+
             repo.find_reference(&branch_ref)
                 .map_err(|e| GitError::CheckoutFailed {
                     branch: branch_name.to_string(),
                     path: repo_path.to_path_buf(),
                     source: e,
                 })?
+        } else if let Ok(tag_ref) = repo.find_reference(&format!("refs/tags/{}", branch_name)) {
+            // Annotated tags are peeled to the commit they tag
+            let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+                branch: branch_name.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            };
+            let commit = tag_ref.peel_to_commit().map_err(checkout_error)?;
+            repo.checkout_tree(commit.as_object(), Some(&mut options.builder()))
+                .map_err(checkout_error)?;
+            repo.set_head_detached(commit.id())
+                .map_err(checkout_error)?;
+            return Ok(CheckoutTarget::DetachedAtTag {
+                commit: commit.id(),
+            });
         } else {
             return Err(GitError::CheckoutFailed {
                 branch: branch_name.to_string(),
                 path: repo_path.to_path_buf(),
                 source: git2::Error::from_str(&format!(
-                    "Branch '{}' not found locally or remotely",
+                    "Branch or tag '{}' not found locally or remotely",
                     branch_name
                 )),
             });
         };
 
         // Set HEAD to the branch
-        let ref_name = reference.name().ok_or_else(|| {
-            GitError::CheckoutFailed {
-                branch: branch_name.to_string(),
-                path: repo_path.to_path_buf(),
-                source: git2::Error::from_str("Reference has no name"),
-            }
+        let ref_name = reference.name().ok_or_else(|| GitError::CheckoutFailed {
+            branch: branch_name.to_string(),
+            path: repo_path.to_path_buf(),
+            source: git2::Error::from_str("Reference has no name"),
         })?;
         repo.set_head(ref_name)
             .map_err(|e| GitError::CheckoutFailed {
@@ -84,14 +337,535 @@ impl GitCheckout {
             })?;
 
         // Checkout the branch
-        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+        repo.checkout_head(Some(&mut options.builder()))
             .map_err(|e| GitError::CheckoutFailed {
                 branch: branch_name.to_string(),
                 path: repo_path.to_path_buf(),
                 source: e,
             })?;
 
+        Ok(CheckoutTarget::Branch)
+    }
+
+    /// Report the working tree files `checkout_branch` would change, without changing them
+    ///
+    /// Files with local modifications are reported as `LocalChangesLost`, since checkout
+    /// overwrites them. Changes are sorted by path.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the branch that would be checked out
+    /// * `tracking_prefix` - Ref prefix the remote's branches are tracked under (e.g. `refs/remotes/origin`)
+    pub fn checkout_dry_run(
+        repo_path: &Path,
+        branch_name: &str,
+        tracking_prefix: &str,
+    ) -> Result<Vec<CheckoutChange>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+            branch: branch_name.to_string(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
+
+        let tree = repo
+            .find_reference(&format!("refs/heads/{branch_name}"))
+            .or_else(|_| repo.find_reference(&format!("{tracking_prefix}/{branch_name}")))
+            .and_then(|reference| reference.peel_to_tree())
+            .map_err(checkout_error)?;
+
+        // Files that differ between HEAD and the target
+        let head_tree = repo.head().and_then(|head| head.peel_to_tree()).ok();
+        let diff = repo
+            .diff_tree_to_tree(head_tree.as_ref(), Some(&tree), None)
+            .map_err(checkout_error)?;
+
+        let mut changes: BTreeMap<PathBuf, CheckoutChangeKind> = BTreeMap::new();
+        for delta in diff.deltas() {
+            let kind = match delta.status() {
+                Delta::Added => CheckoutChangeKind::Added,
+                Delta::Deleted => CheckoutChangeKind::Deleted,
+                _ => CheckoutChangeKind::Modified,
+            };
+            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+                changes.insert(path.to_path_buf(), kind);
+            }
+        }
+
+        // A dry run notifies about files whose local changes a forced checkout would discard
+        {
+            let mut checkout_builder = CheckoutBuilder::new();
+            checkout_builder.dry_run();
+            checkout_builder
+                .notify_on(CheckoutNotificationType::DIRTY | CheckoutNotificationType::CONFLICT);
+            checkout_builder.notify(|_notification, path, _baseline, _target, _workdir| {
+                if let Some(path) = path {
+                    changes.insert(path.to_path_buf(), CheckoutChangeKind::LocalChangesLost);
+                }
+                true
+            });
+
+            match repo.checkout_tree(tree.as_object(), Some(&mut checkout_builder)) {
+                Err(e) if e.code() != ErrorCode::Conflict => return Err(checkout_error(e)),
+                _ => {}
+            }
+        }
+
+        Ok(changes
+            .into_iter()
+            .map(|(path, kind)| CheckoutChange { path, kind })
+            .collect())
+    }
+
+    /// Restore specific paths from a revision without switching branches
+    ///
+    /// This is `git checkout <revspec> -- <paths>`: the paths are written to both the
+    /// index and the working tree, overwriting local changes to them. HEAD doesn't move.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `revspec` - Revision to restore from (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `paths` - Paths or pathspecs to restore; nothing is restored if empty
+    pub fn checkout_paths(repo_path: &Path, revspec: &str, paths: &[&str]) -> Result<(), GitError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+            branch: revspec.to_string(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
+
+        let tree = repo
+            .revparse_single(revspec)
+            .and_then(|object| object.peel_to_tree())
+            .map_err(checkout_error)?;
+
+        let mut checkout_builder = git2::build::CheckoutBuilder::new();
+        checkout_builder.force();
+        for path in paths {
+            checkout_builder.path(path);
+        }
+
+        repo.checkout_tree(tree.as_object(), Some(&mut checkout_builder))
+            .map_err(checkout_error)?;
+
+        Ok(())
+    }
+
+    /// Checkout a commit with a detached HEAD, e.g. to build an exact revision
+    ///
+    /// Local changes to tracked files are overwritten, like `checkout_branch`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `rev` - Revision to checkout (SHA, short SHA, tag, `HEAD~1`, ...)
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `rev` doesn't resolve to a commit
+    pub fn checkout_commit(repo_path: &Path, rev: &str) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| GitError::RevisionNotFound {
+                rev: rev.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            })?;
+
+        let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+            branch: rev.to_string(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
+
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(git2::build::CheckoutBuilder::default().force()),
+        )
+        .map_err(checkout_error)?;
+        repo.set_head_detached(commit.id())
+            .map_err(checkout_error)?;
+
+        Ok(())
+    }
+
+    /// Checkout a commit or tag only after verifying its signature
+    ///
+    /// The exact object that was verified is checked out, so the target can't change
+    /// between verification and checkout. If `target` names a local branch that still
+    /// points at the verified commit, HEAD is set to that branch; otherwise HEAD is detached.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `target` - Branch, tag or commit to checkout
+    /// * `allowed_keys` - GPG fingerprints/long key ids or SSH `SHA256:` fingerprints to trust
+    ///
+    /// # Errors
+    /// Returns `SignatureVerificationFailed` if the target isn't signed by an allowed key
+    pub fn checkout_verified(
+        repo_path: &Path,
+        target: &str,
+        allowed_keys: &[&str],
+    ) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+            branch: target.to_string(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
+
+        let object = repo.revparse_single(target).map_err(checkout_error)?;
+        GitVerify::verify_object(repo_path, &repo, object.id(), allowed_keys)?;
+
+        let commit = object.peel_to_commit().map_err(checkout_error)?;
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(git2::build::CheckoutBuilder::default().force()),
+        )
+        .map_err(checkout_error)?;
+
+        let branch_ref = format!("refs/heads/{}", target);
+        match repo.find_reference(&branch_ref) {
+            Ok(reference) if reference.target() == Some(commit.id()) => {
+                repo.set_head(&branch_ref).map_err(checkout_error)?
+            }
+            _ => repo
+                .set_head_detached(commit.id())
+                .map_err(checkout_error)?,
+        }
+
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo, upstream_and_clone};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_checkout_paths_restores_only_listed_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "a.txt", "a1\n", "Add a");
+        commit_file(&repo, "b.txt", "b1\n", "Add b");
+        commit_file(&repo, "a.txt", "a2\n", "Update a");
+        let head = commit_file(&repo, "b.txt", "b2\n", "Update b");
+
+        GitCheckout::checkout_paths(temp_dir.path(), "HEAD~2", &["a.txt"]).unwrap();
+
+        let read = |name: &str| std::fs::read_to_string(temp_dir.path().join(name)).unwrap();
+        assert_eq!(read("a.txt"), "a1\n");
+        assert_eq!(read("b.txt"), "b2\n");
+        assert_eq!(repo.head().unwrap().target(), Some(head));
+        let status = repo.status_file(Path::new("a.txt")).unwrap();
+        assert!(status.contains(git2::Status::INDEX_MODIFIED));
+    }
+
+    #[test]
+    fn test_checkout_commit_by_short_sha_detaches_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        commit_file(&repo, "file.txt", "two\n", "Second");
+
+        GitCheckout::checkout_commit(temp_dir.path(), &first.to_string()[..7]).unwrap();
+
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "one\n"
+        );
+    }
+
+    #[test]
+    fn test_checkout_commit_unknown_rev() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "First");
+
+        let result = GitCheckout::checkout_commit(temp_dir.path(), "does-not-exist");
+
+        assert!(matches!(
+            result,
+            Err(GitError::RevisionNotFound { rev, .. }) if rev == "does-not-exist"
+        ));
+        assert!(!repo.head_detached().unwrap());
+    }
+
+    #[test]
+    fn test_checkout_verified_rejects_unsigned() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        commit_file(&repo, "file.txt", "two\n", "Second");
+
+        let result =
+            GitCheckout::checkout_verified(temp_dir.path(), &first.to_string(), &["ABCDEF"]);
+
+        assert!(matches!(
+            result,
+            Err(GitError::SignatureVerificationFailed { .. })
+        ));
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/main"));
+    }
+
+    #[test]
+    fn test_checkout_branch_from_namespaced_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        repo.reference(
+            "refs/namespaces/mirror/refs/remotes/origin/feature",
+            commit,
+            false,
+            "test",
+        )
+        .unwrap();
+
+        GitCheckout::checkout_branch(
+            temp_dir.path(),
+            "feature",
+            "refs/namespaces/mirror/refs/remotes/origin",
+            &CheckoutOptions::default(),
+        )
+        .unwrap();
+
+        let head = repo.head().unwrap();
+        assert_eq!(head.name(), Some("refs/heads/feature"));
+        assert_eq!(head.target(), Some(commit));
+    }
+
+    #[test]
+    fn test_checkout_branch_from_remote_tracks_it() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let head = upstream.head().unwrap().peel_to_commit().unwrap();
+        upstream.branch("feature", &head, false).unwrap();
+        local
+            .find_remote("origin")
+            .unwrap()
+            .fetch(
+                &["+refs/heads/feature:refs/remotes/origin/feature"],
+                None,
+                None,
+            )
+            .unwrap();
+
+        GitCheckout::checkout_branch(
+            local.workdir().unwrap(),
+            "feature",
+            "refs/remotes/origin",
+            &CheckoutOptions::default(),
+        )
+        .unwrap();
+
+        let branch = local
+            .find_branch("feature", git2::BranchType::Local)
+            .unwrap();
+        let upstream_branch = branch.upstream().unwrap();
+        assert_eq!(
+            upstream_branch.get().name(),
+            Some("refs/remotes/origin/feature")
+        );
+    }
+
+    #[test]
+    fn test_checkout_branch_without_tracking() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        repo.reference("refs/remotes/origin/feature", commit, false, "test")
+            .unwrap();
+        let options = CheckoutOptions {
+            track: false,
+            ..CheckoutOptions::default()
+        };
+
+        GitCheckout::checkout_branch(temp_dir.path(), "feature", "refs/remotes/origin", &options)
+            .unwrap();
+
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/feature"));
+        assert!(repo
+            .config()
+            .unwrap()
+            .get_string("branch.feature.merge")
+            .is_err());
+    }
+
+    #[test]
+    fn test_checkout_branch_detached_without_creating_local() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        repo.reference("refs/remotes/origin/feature", commit, false, "test")
+            .unwrap();
+        let options = CheckoutOptions {
+            create_local: false,
+            ..CheckoutOptions::default()
+        };
+
+        let target = GitCheckout::checkout_branch(
+            temp_dir.path(),
+            "feature",
+            "refs/remotes/origin",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(target, CheckoutTarget::DetachedAtRemote { commit });
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap().target(), Some(commit));
+        assert!(repo.find_reference("refs/heads/feature").is_err());
+    }
+
+    #[test]
+    fn test_checkout_branch_falls_back_to_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        let second = commit_file(&repo, "file.txt", "two\n", "Second");
+        repo.tag_lightweight("v1", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        let signature = repo.signature().unwrap();
+        repo.tag(
+            "v2",
+            &repo.find_object(second, None).unwrap(),
+            &signature,
+            "Release 2",
+            false,
+        )
+        .unwrap();
+        let options = CheckoutOptions::default();
+
+        let lightweight =
+            GitCheckout::checkout_branch(temp_dir.path(), "v1", "refs/remotes/origin", &options)
+                .unwrap();
+
+        assert_eq!(lightweight, CheckoutTarget::DetachedAtTag { commit: first });
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "one\n"
+        );
+
+        let annotated =
+            GitCheckout::checkout_branch(temp_dir.path(), "v2", "refs/remotes/origin", &options)
+                .unwrap();
+
+        assert_eq!(annotated, CheckoutTarget::DetachedAtTag { commit: second });
+        assert_eq!(repo.head().unwrap().target(), Some(second));
+        assert!(repo.find_reference("refs/heads/v2").is_err());
+    }
+
+    #[test]
+    fn test_checkout_branch_restricted_to_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "kept.txt", "one\n", "Add kept");
+        commit_file(&repo, "updated.txt", "one\n", "Add updated");
+        let main = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &main, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        commit_file(&repo, "kept.txt", "two\n", "Change kept");
+        commit_file(&repo, "updated.txt", "two\n", "Change updated");
+        repo.set_head("refs/heads/main").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        let notified = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = notified.clone();
+        let options = CheckoutOptions {
+            paths: vec!["updated.txt".to_string()],
+            notify: Some(CheckoutNotifier::new(move |notification, path| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((notification, path.to_path_buf()));
+            })),
+            ..CheckoutOptions::default()
+        };
+
+        GitCheckout::checkout_branch(temp_dir.path(), "feature", "refs/remotes/origin", &options)
+            .unwrap();
+
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/feature"));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("updated.txt")).unwrap(),
+            "two\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("kept.txt")).unwrap(),
+            "one\n"
+        );
+        let notified = notified.lock().unwrap();
+        assert!(notified.contains(&(CheckoutNotification::Updated, PathBuf::from("updated.txt"))));
+        assert!(notified
+            .iter()
+            .all(|(_, path)| path == Path::new("updated.txt")));
+    }
+
+    #[test]
+    fn test_checkout_dry_run_reports_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "kept.txt", "kept\n", "Add kept");
+        commit_file(&repo, "changed.txt", "one\n", "Add changed");
+        commit_file(&repo, "dirty.txt", "one\n", "Add dirty");
+        commit_file(&repo, "conflict.txt", "one\n", "Add conflict");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        commit_file(&repo, "changed.txt", "two\n", "Update changed");
+        commit_file(&repo, "added.txt", "new\n", "Add added");
+        commit_file(&repo, "conflict.txt", "two\n", "Update conflict");
+        std::fs::write(temp_dir.path().join("dirty.txt"), "local\n").unwrap();
+        std::fs::write(temp_dir.path().join("conflict.txt"), "local\n").unwrap();
+        let head = repo.head().unwrap().target();
+
+        let changes =
+            GitCheckout::checkout_dry_run(temp_dir.path(), "feature", "refs/remotes/origin")
+                .unwrap();
+
+        let kinds: Vec<(&str, CheckoutChangeKind)> = changes
+            .iter()
+            .map(|change| (change.path.to_str().unwrap(), change.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("added.txt", CheckoutChangeKind::Deleted),
+                ("changed.txt", CheckoutChangeKind::Modified),
+                ("conflict.txt", CheckoutChangeKind::LocalChangesLost),
+                ("dirty.txt", CheckoutChangeKind::LocalChangesLost),
+            ]
+        );
+        assert_eq!(repo.head().unwrap().target(), head);
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("dirty.txt")).unwrap(),
+            "local\n"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use git2::build::CheckoutBuilder;
+use git2::{CherrypickOptions, Oid, Repository};
+use std::path::Path;
+
+use crate::checkout::ConflictStyle;
+use crate::error::GitError;
+
+/// Cherry-pick operations for Git repositories
+pub struct GitCherryPick;
+
+impl GitCherryPick {
+    /// Cherry-pick `commits` in order onto the local branch `onto`, e.g. to backport a PR
+    ///
+    /// `onto` is checked out first and ends up pointing at the last applied commit. Each new
+    /// commit keeps the original author and message and is committed with the repository's
+    /// signature. Commits whose changes are already on the branch are skipped.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commits` - Commits to apply, oldest first
+    /// * `onto` - Name of the local branch to apply them to
+    /// * `conflict_style` - Style of conflict markers to write, `None` to follow `merge.conflictStyle`
+    ///
+    /// # Returns
+    /// The ids of the new commits, in order
+    ///
+    /// # Errors
+    /// Returns `TransplantConflict` if a commit doesn't apply cleanly. The conflicted
+    /// cherry-pick is left in the index and working tree for resolution, with the commits
+    /// before it already committed.
+    pub fn transplant(
+        repo_path: &Path,
+        commits: &[Oid],
+        onto: &str,
+        conflict_style: Option<ConflictStyle>,
+    ) -> Result<Vec<Oid>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+            branch: onto.to_string(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
+
+        let branch_ref = format!("refs/heads/{onto}");
+        let branch_tip = repo
+            .find_reference(&branch_ref)
+            .and_then(|reference| reference.peel_to_commit())
+            .map_err(checkout_error)?;
+        repo.checkout_tree(branch_tip.as_object(), None)
+            .map_err(checkout_error)?;
+        repo.set_head(&branch_ref).map_err(checkout_error)?;
+
+        let signature = repo.signature()?;
+        let mut applied = Vec::new();
+
+        for (index, commit_id) in commits.iter().enumerate() {
+            let commit = repo.find_commit(*commit_id)?;
+            let mut cherrypick_options = CherrypickOptions::new();
+            if let Some(conflict_style) = conflict_style {
+                // Any explicit strategy replaces libgit2's default of safe with conflicts allowed
+                let mut checkout_builder = CheckoutBuilder::new();
+                checkout_builder.safe().allow_conflicts(true);
+                conflict_style.apply(&mut checkout_builder);
+                cherrypick_options.checkout_builder(checkout_builder);
+            }
+            repo.cherrypick(&commit, Some(&mut cherrypick_options))?;
+
+            let mut repo_index = repo.index()?;
+            if repo_index.has_conflicts() {
+                return Err(GitError::TransplantConflict {
+                    path: repo_path.to_path_buf(),
+                    commit: *commit_id,
+                    index,
+                    applied: applied.len(),
+                    total: commits.len(),
+                });
+            }
+
+            let tree = repo.find_tree(repo_index.write_tree()?)?;
+            let parent = repo.head()?.peel_to_commit()?;
+            if tree.id() != parent.tree_id() {
+                let new_commit = repo.commit(
+                    Some("HEAD"),
+                    &commit.author(),
+                    &signature,
+                    commit.message().unwrap_or(""),
+                    &tree,
+                    &[&parent],
+                )?;
+                applied.push(new_commit);
+            }
+
+            repo.cleanup_state()?;
+        }
+
+        Ok(applied)
+    }
+
+    /// Cherry-pick `rev` onto the current branch, like `git cherry-pick`
+    ///
+    /// The new commit keeps the original author and message and is committed with the
+    /// repository's signature.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `rev` - Commit to apply (branch, tag, SHA, `HEAD~1`, ...)
+    ///
+    /// # Returns
+    /// The new commit, or `None` if the current branch already has the commit's changes
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `rev` doesn't resolve to a commit, and `MergeRequired` if
+    /// it doesn't apply cleanly, in which case the files it touched are restored and the
+    /// branch is left as it was
+    pub fn cherry_pick(repo_path: &Path, rev: &str) -> Result<Option<Oid>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| GitError::RevisionNotFound {
+                rev: rev.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            })?;
+        let head = repo.head()?.peel_to_commit()?;
+
+        repo.cherrypick(&commit, None)?;
+
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            abort_cherry_pick(&repo, &head, &commit)?;
+            return Err(GitError::MergeRequired {
+                path: repo_path.to_path_buf(),
+                local: head.id(),
+                remote: commit.id(),
+            });
+        }
+
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let new_commit = if tree.id() == head.tree_id() {
+            None
+        } else {
+            let signature = repo.signature()?;
+            Some(repo.commit(
+                Some("HEAD"),
+                &commit.author(),
+                &signature,
+                commit.message().unwrap_or(""),
+                &tree,
+                &[&head],
+            )?)
+        };
+        repo.cleanup_state()?;
+
+        Ok(new_commit)
+    }
+}
+
+/// Undo a conflicted cherry-pick of `commit` onto `head`, like `git cherry-pick --abort`
+///
+/// Only the files `commit` changes are restored; a cherry-pick refuses to touch files with
+/// local changes, so other local changes survive.
+fn abort_cherry_pick(
+    repo: &Repository,
+    head: &git2::Commit,
+    commit: &git2::Commit,
+) -> Result<(), git2::Error> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let paths: Vec<&Path> = diff
+        .deltas()
+        .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+        .flatten()
+        .collect();
+
+    repo.reset_default(Some(head.as_object()), &paths)?;
+    let mut checkout_builder = CheckoutBuilder::new();
+    checkout_builder.force().remove_untracked(true);
+    for path in &paths {
+        checkout_builder.path(path);
+    }
+    repo.checkout_head(Some(&mut checkout_builder))?;
+    repo.cleanup_state()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use git2::RepositoryState;
+    use tempfile::TempDir;
+
+    /// A repository with a `release` branch at the first commit and `main` ahead of it
+    fn backport_repo(temp_dir: &TempDir) -> Repository {
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("release", &base, false).unwrap();
+        drop(base);
+        repo
+    }
+
+    #[test]
+    fn test_cherry_pick_commits_onto_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = backport_repo(&temp_dir);
+        commit_file(
+            &repo,
+            "fix.txt",
+            "fixed\n",
+            "Fix the bug\n\nLonger description",
+        );
+        repo.set_head("refs/heads/release").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        let release_tip = repo.head().unwrap().target().unwrap();
+
+        let picked = GitCherryPick::cherry_pick(temp_dir.path(), "main").unwrap();
+
+        let tip = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(picked, Some(tip.id()));
+        assert_eq!(tip.parent_id(0).unwrap(), release_tip);
+        assert_eq!(tip.summary(), Some("Fix the bug"));
+        assert_eq!(tip.author().name(), Some("Test User"));
+        assert!(temp_dir.path().join("fix.txt").exists());
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict_restores_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = backport_repo(&temp_dir);
+        let conflicting = commit_file(&repo, "file.txt", "main\n", "Change file");
+        repo.set_head("refs/heads/release").unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        let release_tip = commit_file(&repo, "file.txt", "release\n", "Release change");
+
+        let result = GitCherryPick::cherry_pick(temp_dir.path(), &conflicting.to_string());
+
+        assert!(matches!(
+            result,
+            Err(GitError::MergeRequired { local, remote, .. })
+                if local == release_tip && remote == conflicting
+        ));
+        assert_eq!(repo.head().unwrap().target(), Some(release_tip));
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(!repo.index().unwrap().has_conflicts());
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "release\n"
+        );
+    }
+
+    #[test]
+    fn test_transplant_applies_commits_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = backport_repo(&temp_dir);
+        commit_file(&repo, "unrelated.txt", "skip\n", "Unrelated");
+        let fix_a = commit_file(&repo, "a.txt", "a\n", "Fix a");
+        let fix_b = commit_file(&repo, "b.txt", "b\n", "Fix b");
+
+        let applied =
+            GitCherryPick::transplant(temp_dir.path(), &[fix_a, fix_b], "release", None).unwrap();
+
+        let head = repo.head().unwrap();
+        assert_eq!(head.name(), Some("refs/heads/release"));
+        let tip = head.peel_to_commit().unwrap();
+        assert_eq!(applied, vec![tip.parent_id(0).unwrap(), tip.id()]);
+        assert_eq!(tip.summary(), Some("Fix b"));
+        assert!(temp_dir.path().join("a.txt").exists());
+        assert!(!temp_dir.path().join("unrelated.txt").exists());
+        assert_eq!(repo.state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_transplant_stops_on_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = backport_repo(&temp_dir);
+        let fix_a = commit_file(&repo, "a.txt", "a\n", "Fix a");
+        let conflicting = commit_file(&repo, "file.txt", "main\n", "Change file");
+        repo.set_head("refs/heads/release").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(&repo, "file.txt", "release\n", "Release change");
+
+        let result =
+            GitCherryPick::transplant(temp_dir.path(), &[fix_a, conflicting], "release", None);
+
+        match result {
+            Err(GitError::TransplantConflict {
+                commit,
+                index,
+                applied,
+                total,
+                ..
+            }) => {
+                assert_eq!(commit, conflicting);
+                assert_eq!(index, 1);
+                assert_eq!(applied, 1);
+                assert_eq!(total, 2);
+            }
+            other => panic!("expected TransplantConflict, got {other:?}"),
+        }
+        assert_eq!(repo.state(), RepositoryState::CherryPick);
+        let tip = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(tip.summary(), Some("Fix a"));
+    }
+
+    #[test]
+    fn test_transplant_conflict_does_not_count_skipped_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = backport_repo(&temp_dir);
+        let fix_a = commit_file(&repo, "a.txt", "a\n", "Fix a");
+        let conflicting = commit_file(&repo, "file.txt", "main\n", "Change file");
+        repo.set_head("refs/heads/release").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(&repo, "a.txt", "a\n", "Fix a on release");
+        commit_file(&repo, "file.txt", "release\n", "Release change");
+
+        let result =
+            GitCherryPick::transplant(temp_dir.path(), &[fix_a, conflicting], "release", None);
+
+        match result {
+            Err(GitError::TransplantConflict {
+                commit,
+                index,
+                applied,
+                ..
+            }) => {
+                assert_eq!(commit, conflicting);
+                assert_eq!(index, 1);
+                assert_eq!(applied, 0);
+            }
+            other => panic!("expected TransplantConflict, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_transplant_conflict_diff3_style() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = backport_repo(&temp_dir);
+        let conflicting = commit_file(&repo, "file.txt", "main\n", "Change file");
+        repo.set_head("refs/heads/release").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        commit_file(&repo, "file.txt", "release\n", "Release change");
+
+        let result = GitCherryPick::transplant(
+            temp_dir.path(),
+            &[conflicting],
+            "release",
+            Some(ConflictStyle::Diff3),
+        );
+
+        assert!(matches!(result, Err(GitError::TransplantConflict { .. })));
+        let contents = std::fs::read_to_string(temp_dir.path().join("file.txt")).unwrap();
+        assert!(contents.contains("|||||||"));
+        assert!(contents.contains("one\n"));
+    }
+}
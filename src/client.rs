@@ -1,48 +1,1446 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use crate::auth::SshConfig;
-use crate::checkout::GitCheckout;
+use crate::auth::{SshConfig, TokenAuth};
+use crate::author::Author;
+use crate::batch::{self, BatchOptions};
+use crate::branch::{BranchFilter, BranchInfo, GitBranch};
+use crate::builder::GitClientBuilder;
+use crate::bundle::GitBundle;
+use crate::checkout::{
+    CheckoutChange, CheckoutNotifier, CheckoutOptions, CheckoutTarget, ConflictStyle, GitCheckout,
+};
+use crate::cherry_pick::GitCherryPick;
+use crate::clone::GitCloner;
+use crate::commit::GitCommit;
+use crate::diff::{FileDiff, GitDiff};
 use crate::error::GitError;
-use crate::pull::GitPuller;
+use crate::fetch::FetchConfig;
+use crate::lock::RepoLock;
+use crate::log::{CommitInfo, GitLog, LogOptions};
+use crate::merge::{CommitId, GitMerge, MergeOutcome};
+use crate::notes::GitNotes;
+use crate::progress::Progress;
+use crate::pull::{DryRunOutcome, FetchSummary, GitPuller, MergeStrategy, PullOutcome};
+use crate::push::GitPusher;
+use crate::refs::GitRefs;
+use crate::remote::GitRemote;
+use crate::repository::{GitRepository, OpProgress, RepoInfo};
+use crate::reset::{GitReset, ResetMode};
+use crate::retry::RetryPolicy;
+use crate::revision::{DescribeOptions, GitRevision};
+use crate::stash::GitStash;
+use crate::status::{DirtyOptions, GitStatus, StatusEntry};
+use crate::submodule::{GitSubmodule, GitmoduleEntry};
+use crate::tag::GitTag;
 
 /// Git operations client that handles repository pulling and checkout with SSH authentication
+#[derive(Clone)]
 pub struct GitClient {
+    cloner: GitCloner,
     puller: GitPuller,
+    pusher: GitPusher,
+    remote: GitRemote,
+    reject_submodules: bool,
+    conflict_style: Option<ConflictStyle>,
+    lock_timeout: Option<Duration>,
 }
 
 impl GitClient {
     /// Create a new GitClient with default SSH configuration
     pub fn new() -> Result<Self, GitError> {
-        let ssh_config = SshConfig::from_environment()?;
-        let puller = GitPuller::new(ssh_config);
+        GitClientBuilder::new().build()
+    }
 
-        Ok(Self { puller })
+    /// Start configuring a GitClient, see `GitClientBuilder`
+    pub fn builder() -> GitClientBuilder {
+        GitClientBuilder::new()
     }
 
     /// Create a new GitClient with custom SSH configuration
     pub fn with_ssh_config(ssh_config: SshConfig) -> Self {
-        let puller = GitPuller::new(ssh_config);
-        Self { puller }
+        let cloner = GitCloner::new(ssh_config.clone());
+        let puller = GitPuller::new(ssh_config.clone());
+        let pusher = GitPusher::new(ssh_config.clone());
+        let remote = GitRemote::new(ssh_config);
+        Self {
+            cloner,
+            puller,
+            pusher,
+            remote,
+            reject_submodules: false,
+            conflict_style: None,
+            lock_timeout: None,
+        }
+    }
+
+    /// Get the fetch configuration used by pull and checkout
+    pub fn fetch_config(&self) -> &FetchConfig {
+        self.puller.fetch_config()
+    }
+
+    /// Set the fetch configuration used by pull and checkout
+    ///
+    /// Its depth applies to clones too.
+    pub fn set_fetch_config(&mut self, fetch_config: FetchConfig) {
+        self.cloner.set_depth(fetch_config.depth());
+        self.puller.set_fetch_config(fetch_config);
+    }
+
+    /// Get the retries for pull, fetch and clone transfers that fail with transient network errors
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.puller.retry_policy()
+    }
+
+    /// Clone without a working tree, like `git clone --bare`
+    pub fn set_bare_clone(&mut self, bare: bool) {
+        self.cloner.set_bare(bare);
+    }
+
+    /// Clone repositories as mirrors, like `git clone --mirror`
+    ///
+    /// Mirrors are bare and copy every remote ref (branches, tags, notes, ...) under its own
+    /// name. Pulling a mirror fetches all refs again, so backups stay in sync.
+    pub fn set_mirror_clone(&mut self, mirror: bool) {
+        self.cloner.set_mirror(mirror);
+    }
+
+    /// Retry pull, fetch and clone transfers that fail with transient network errors
+    ///
+    /// Connection, DNS and HTTP failures are retried with a doubling delay; other errors,
+    /// such as rejected credentials, fail immediately. Defaults to a single attempt.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.cloner.set_retry_policy(retry_policy);
+        self.puller.set_retry_policy(retry_policy);
+    }
+
+    /// Sign merge and rebased commits made by pulls as `signature` instead of git config's identity
+    ///
+    /// With `None`, the default, `user.name` and `user.email` are used, falling back to
+    /// `git-ops <git-ops@localhost>` so pulls still work where no identity is configured.
+    pub fn set_signature(&mut self, signature: Option<Author>) {
+        self.puller.set_signature(signature);
+    }
+
+    /// Stash uncommitted changes around pulls that merge or rebase, like `git pull --autostash`
+    ///
+    /// Only applies when the merge strategy is `Merge` or `Rebase`. If the changes can't be
+    /// reapplied afterwards, the pull fails with `AutostashPopFailed` and they stay in the stash.
+    pub fn set_autostash(&mut self, autostash: bool) {
+        self.puller.set_autostash(autostash);
+    }
+
+    /// Update submodules recursively after each pull
+    pub fn set_update_submodules(&mut self, update_submodules: bool) {
+        self.puller.set_update_submodules(update_submodules);
+    }
+
+    /// Report the files a pull's checkout updates or finds in its way to `notifier`
+    ///
+    /// Pass `None` to stop reporting them.
+    pub fn set_checkout_notifier(&mut self, notifier: Option<CheckoutNotifier>) {
+        self.puller.set_checkout_notifier(notifier);
+    }
+
+    /// Let fast-forward pulls overwrite uncommitted changes to the files they update
+    ///
+    /// Off by default: such a pull fails with `DirtyWorkingTree` instead.
+    pub fn set_force_checkout(&mut self, force_checkout: bool) {
+        self.puller.set_force_checkout(force_checkout);
+    }
+
+    /// Offer a token to HTTPS remotes before the git credential helper and `GITHUB_TOKEN`-style variables
+    ///
+    /// Useful for short-lived tokens minted by the caller. Pass `None` to stop offering it.
+    pub fn set_https_token(&mut self, https_token: Option<String>) {
+        self.cloner.set_https_token(https_token.clone());
+        self.puller.set_https_token(https_token.clone());
+        self.pusher.set_https_token(https_token.clone());
+        self.remote.set_https_token(https_token);
+    }
+
+    /// Choose the environment variables HTTPS tokens are read from when the credential helper has none
+    ///
+    /// Defaults to `GITHUB_TOKEN`, `GH_TOKEN` and `GITHUB_ACCESS_TOKEN`. Add e.g.
+    /// `GITLAB_TOKEN` with username `oauth2` for GitLab.
+    pub fn set_token_auth(&mut self, token_auth: TokenAuth) {
+        self.cloner.set_token_auth(token_auth.clone());
+        self.puller.set_token_auth(token_auth.clone());
+        self.pusher.set_token_auth(token_auth.clone());
+        self.remote.set_token_auth(token_auth);
+    }
+
+    /// Stop warning that no git credential helper is configured when HTTPS credentials are needed
+    ///
+    /// Useful in CI, where tokens come from the environment. See
+    /// `TokenAuth::set_credential_helper_warning`.
+    pub fn set_suppress_credential_warnings(&mut self, suppress: bool) {
+        let mut token_auth = self.puller.token_auth().clone();
+        token_auth.set_credential_helper_warning(!suppress);
+        self.set_token_auth(token_auth);
+    }
+
+    /// Get the proxy for HTTP(S) remotes, `None` if it's detected from git config and the environment
+    pub fn proxy(&self) -> Option<&str> {
+        self.puller.proxy()
+    }
+
+    /// Connect to HTTP(S) remotes through `proxy`, e.g. `http://proxy.example.com:8080`
+    ///
+    /// With `None`, the default, the proxy is detected from `http.proxy` and
+    /// `remote.<name>.proxy` in git config, then the `HTTPS_PROXY`/`HTTP_PROXY` environment
+    /// variables. Only HTTPS remotes are tunnelled through the proxy.
+    pub fn set_proxy(&mut self, proxy: Option<String>) {
+        self.cloner.set_proxy(proxy.clone());
+        self.puller.set_proxy(proxy.clone());
+        self.pusher.set_proxy(proxy.clone());
+        self.remote.set_proxy(proxy);
+    }
+
+    /// Call `callback` with transfer progress while cloning and fetching
+    ///
+    /// Replaces any earlier callback. The callback runs on the thread doing the transfer.
+    pub fn set_progress_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        let callback = Arc::new(callback);
+        let clone_callback = callback.clone();
+        self.cloner
+            .set_progress_callback(move |progress| clone_callback(progress));
+        self.puller
+            .set_progress_callback(move |progress| callback(progress));
+    }
+
+    /// Stop reporting transfer progress
+    pub fn clear_progress_callback(&mut self) {
+        self.cloner.clear_progress_callback();
+        self.puller.clear_progress_callback();
+    }
+
+    /// Report progress at most once per `interval`, plus once when the transfer completes
+    ///
+    /// `None`, the default, reports every update libgit2 sends.
+    pub fn set_progress_interval(&mut self, interval: Option<Duration>) {
+        self.cloner.set_progress_interval(interval);
+        self.puller.set_progress_interval(interval);
+    }
+
+    /// Get the remote that pull, fetch, push, checkout and reset operate on
+    pub fn remote_name(&self) -> &str {
+        self.puller.remote_name()
+    }
+
+    /// Operate on `remote_name` instead of `origin`, e.g. `upstream`
+    ///
+    /// Pulls and fetches use it, remote-tracking branches are looked up under its tracking
+    /// prefix (`refs/remotes/<remote_name>` by default), and pushes and access checks target it.
+    pub fn set_remote_name(&mut self, remote_name: String) {
+        self.puller.set_remote_name(remote_name.clone());
+        self.pusher.set_remote_name(remote_name);
+    }
+
+    /// Get how `pull`, `pull_detailed` and `pull_if_behind` reconcile a diverged local branch
+    pub fn merge_strategy(&self) -> MergeStrategy {
+        self.puller.merge_strategy()
+    }
+
+    /// Reconcile a diverged local branch with `merge_strategy` in `pull`, `pull_detailed` and `pull_if_behind`
+    ///
+    /// Defaults to `MergeStrategy::FastForwardOnly`, which fails with `MergeRequired`.
+    /// `pull_with_strategy` still uses the strategy it's given.
+    pub fn set_merge_strategy(&mut self, merge_strategy: MergeStrategy) {
+        self.puller.set_merge_strategy(merge_strategy);
+    }
+
+    /// Check if operations refuse to modify repositories that are submodules of another repository
+    pub fn rejects_submodules(&self) -> bool {
+        self.reject_submodules
+    }
+
+    /// Refuse to pull, reset or checkout in a repository that is a submodule of another repository
+    ///
+    /// When enabled, these operations fail fast with `GitError::InsideSubmodule` instead of
+    /// acting on a repository the caller may not have meant to target.
+    pub fn set_reject_submodules(&mut self, reject_submodules: bool) {
+        self.reject_submodules = reject_submodules;
+    }
+
+    /// Get the style conflict markers are written in, `None` if `merge.conflictStyle` decides
+    pub fn conflict_style(&self) -> Option<ConflictStyle> {
+        self.conflict_style
+    }
+
+    /// Write conflict markers in `conflict_style` when an operation leaves conflicted files
+    ///
+    /// Overrides the repository's `merge.conflictStyle` for operations run through this client,
+    /// e.g. `Diff3` so conflict tooling always sees the base version. Pass `None` to follow the
+    /// repository's configuration again.
+    pub fn set_conflict_style(&mut self, conflict_style: Option<ConflictStyle>) {
+        self.conflict_style = conflict_style;
+    }
+
+    /// Get how long mutating operations wait for the repository's advisory lock, `None` if locking is off
+    pub fn lock_timeout(&self) -> Option<Duration> {
+        self.lock_timeout
+    }
+
+    /// Serialize mutating operations on a repository across processes using an advisory lock
+    ///
+    /// When set, every operation that writes to the repository (commits, checkouts, fetches,
+    /// pulls, pushes, merges, resets, ref and note updates) first takes an OS file lock on
+    /// `.git/git-ops.lock`, waiting up to `lock_timeout` before failing with
+    /// `GitError::RepoLocked`. The lock is released when the holder exits, even if it crashes.
+    /// Only processes using this lock are excluded; plain `git` commands are not. Pass `None`
+    /// to stop locking.
+    pub fn set_lock_timeout(&mut self, lock_timeout: Option<Duration>) {
+        self.lock_timeout = lock_timeout;
+    }
+
+    /// Clone a repository into `dest` and check out its default branch
+    ///
+    /// SSH or HTTPS credentials are picked from the URL just like `pull`. `dest` is removed
+    /// if the clone fails.
+    ///
+    /// # Arguments
+    /// * `url` - URL of the repository to clone
+    /// * `dest` - Directory to clone into; must not exist or be empty
+    pub fn clone_repo(&self, url: &str, dest: &Path) -> Result<(), GitError> {
+        self.cloner.clone_repo(url, dest)
+    }
+
+    /// Clone a repository, checking out only the paths matching `patterns`
+    ///
+    /// The working tree only contains matching files, and the clone is configured as a
+    /// sparse checkout so the git command line agrees. `dest` is removed if any step fails.
+    ///
+    /// # Arguments
+    /// * `url` - URL of the repository to clone
+    /// * `dest` - Directory to clone into; must not exist or be empty
+    /// * `patterns` - Pathspecs to check out, e.g. `services/api` or `docs/*.md`
+    pub fn clone_sparse(&self, url: &str, dest: &Path, patterns: &[&str]) -> Result<(), GitError> {
+        self.cloner.clone_sparse(url, dest, patterns)
+    }
+
+    /// Initialize and update a repository's submodules to the commits it records, like `git submodule update --init`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the superproject
+    /// * `recursive` - Also update submodules of submodules
+    pub fn update_submodules(&self, repo_path: &Path, recursive: bool) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.update_submodules(repo_path, recursive)
     }
 
     /// Pull updates for an existing repository
     ///
+    /// A bare repository, such as a mirror clone, is only fetched, updating its refs.
+    ///
     /// # Arguments
     /// * `repo_path` - Path to the repository to update
     pub fn pull(&self, repo_path: &Path) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
         self.puller.pull(repo_path)
     }
 
-    /// Checkout a branch in the repository
+    /// Pull updates like `pull`, reporting whether the branch was fast-forwarded or already up to date
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    pub fn pull_detailed(&self, repo_path: &Path) -> Result<PullOutcome, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.pull_detailed(repo_path)
+    }
+
+    /// Fetch and report what `pull_detailed` would do, leaving the local branch and working tree alone
+    ///
+    /// The fetch still updates remote-tracking refs such as `refs/remotes/origin/main`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn pull_dry_run(&self, repo_path: &Path) -> Result<DryRunOutcome, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.pull_dry_run(repo_path)
+    }
+
+    /// Pull updates like `pull`, aborting the fetch with `Cancelled` once `cancel` is set
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    /// * `cancel` - Flag another thread sets to stop the pull
+    pub fn pull_with_cancel(
+        &self,
+        repo_path: &Path,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.pull_with_cancel(repo_path, cancel)
+    }
+
+    /// Pull updates, reconciling a diverged local branch with `strategy`
+    ///
+    /// `pull` uses the strategy from `set_merge_strategy` instead. A conflicting merge or rebase leaves the
+    /// branch and working tree as they were and fails with `MergeRequired`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    /// * `strategy` - What to do when the local branch has diverged from the remote
+    pub fn pull_with_strategy(
+        &self,
+        repo_path: &Path,
+        strategy: MergeStrategy,
+    ) -> Result<PullOutcome, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.pull_with_strategy(repo_path, strategy)
+    }
+
+    /// Fetch the configured remote (`origin` by default) into its remote-tracking refs without touching HEAD or the working tree
+    ///
+    /// Useful for inspecting incoming changes, e.g. in CI, before pulling them.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn fetch(&self, repo_path: &Path) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.fetch(repo_path)
+    }
+
+    /// Pull only if the remote branch moved since the last fetch
+    ///
+    /// A lightweight listing of the remote's refs is done first, and the full fetch is skipped
+    /// when the tracked branch is unchanged. Useful for polling many idle repositories.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    pub fn pull_if_behind(&self, repo_path: &Path) -> Result<PullOutcome, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.pull_if_behind(repo_path)
+    }
+
+    /// Fetch a remote branch and merge it into HEAD, reporting each step's outcome separately
+    ///
+    /// Useful when the fetch and the merge need to be logged or timed independently. A
+    /// diverged HEAD gets a merge commit; a conflicting merge fails with `MergeRequired`
+    /// and leaves the working tree untouched.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Remote to fetch from
+    /// * `branch` - Name of the branch on the remote to merge
+    pub fn fetch_then_merge(
+        &self,
+        repo_path: &Path,
+        remote_name: &str,
+        branch: &str,
+    ) -> Result<(FetchSummary, MergeOutcome), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.fetch_then_merge(repo_path, remote_name, branch)
+    }
+
+    /// Resolve a reference to a commit that can be passed to `merge`, for merge flows `pull` doesn't cover
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `refname` - Reference or revision to resolve, e.g. `FETCH_HEAD`, `origin/main` or `v1.0.0`
+    pub fn annotated_commit_from_ref(
+        &self,
+        repo_path: &Path,
+        refname: &str,
+    ) -> Result<CommitId, GitError> {
+        GitMerge::annotated_commit_from_ref(repo_path, refname)
+    }
+
+    /// Merge a commit into HEAD, fast-forwarding when possible and creating a merge commit otherwise
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commit` - Commit to merge, from `annotated_commit_from_ref`
+    ///
+    /// # Errors
+    /// Returns `MergeRequired` without changing anything if the merge conflicts
+    pub fn merge(&self, repo_path: &Path, commit: &CommitId) -> Result<MergeOutcome, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitMerge::merge(repo_path, commit)
+    }
+
+    /// Fetch every remote of the repository, like `git fetch --all`, e.g. to keep `origin` and a backup in sync
+    ///
+    /// Each remote is authenticated according to its own URL (SSH or HTTPS).
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// Each remote's name with its transfer statistics, in name order
+    pub fn fetch_all_remotes(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Vec<(String, FetchSummary)>, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.fetch_all_remotes(repo_path)
+    }
+
+    /// Fetch the full history of a shallow clone, e.g. before a `describe` that needs older tags
+    ///
+    /// Requires the `git` command line tool, which authenticates with git's own credential
+    /// helpers and SSH setup. Does nothing if the repository isn't shallow.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Remote to fetch the missing history from
+    pub fn unshallow(&self, repo_path: &Path, remote_name: &str) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.unshallow(repo_path, remote_name)
+    }
+
+    /// Fetch one refspec and check out the fetched tip on a local branch in a single call
+    ///
+    /// Intended for CI checkouts of a specific ref such as a pull request head; the repository
+    /// is opened only once. Local modifications in the way fail the checkout unless `force`
+    /// is set, in which case they're discarded.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Remote to fetch from
+    /// * `refspec` - Refspec to fetch, e.g. `+refs/pull/42/head:refs/remotes/origin/pr/42`
+    /// * `local_ref` - Branch to check out the tip on, e.g. `pr-42` or `refs/heads/pr-42`
+    /// * `force` - Discard local modifications that are in the way instead of failing
+    ///
+    /// # Returns
+    /// The fetched commit
+    pub fn fetch_and_checkout(
+        &self,
+        repo_path: &Path,
+        remote_name: &str,
+        refspec: &str,
+        local_ref: &str,
+        force: bool,
+    ) -> Result<git2::Oid, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller
+            .fetch_and_checkout(repo_path, remote_name, refspec, local_ref, force)
+    }
+
+    /// Check if a local branch can be fast-forwarded to its remote-tracking branch
+    ///
+    /// This is a cheap local check against the last fetch, e.g. to decide whether a pull
+    /// will be a clean fast-forward. Nothing is fetched.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch
+    ///
+    /// # Returns
+    /// `true` if the remote-tracking branch is strictly ahead, `false` if equal or diverged
+    pub fn can_fast_forward(&self, repo_path: &Path, branch_name: &str) -> Result<bool, GitError> {
+        self.puller.can_fast_forward(repo_path, branch_name)
+    }
+
+    /// Fetch, then check if pulling the current branch would be a fast-forward
+    ///
+    /// Unlike `can_fast_forward` this contacts the remote, e.g. so a scheduler can skip pulls
+    /// that would do nothing or fail. Only remote-tracking refs are updated.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `true` if the fetched branch is strictly ahead, `false` if equal or diverged
+    pub fn fetch_can_fast_forward(&self, repo_path: &Path) -> Result<bool, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.fetch_can_fast_forward(repo_path)
+    }
+
+    /// Pull updates for many repositories concurrently
+    ///
+    /// At most `options.max_concurrent` repositories are pulled at once, and no more than
+    /// `options.max_per_host` of those connect to the same host (taken from each repository's
+    /// remote's URL), so one server isn't flooded while repositories on other hosts keep going.
+    ///
+    /// # Arguments
+    /// * `repo_paths` - Paths to the repositories to update
+    /// * `options` - Concurrency limits for the batch
+    ///
+    /// # Returns
+    /// The result for each repository, in the same order as `repo_paths`
+    pub fn pull_many(
+        &self,
+        repo_paths: &[&Path],
+        options: &BatchOptions,
+    ) -> Vec<(PathBuf, Result<(), GitError>)> {
+        let hosts: Vec<Option<String>> = repo_paths
+            .iter()
+            .map(|repo_path| GitRemote::remote_host(repo_path, self.remote_name()))
+            .collect();
+
+        batch::run(repo_paths, &hosts, options, |repo_path| {
+            self.pull(repo_path)
+        })
+    }
+
+    /// Pull updates for many repositories, reporting what happened to each
+    ///
+    /// Like `pull_many` with `BatchOptions::default()`, but each repository is updated with
+    /// `pull_detailed`. A failing repository doesn't stop the others.
+    ///
+    /// # Arguments
+    /// * `repo_paths` - Paths to the repositories to update
+    ///
+    /// # Returns
+    /// The outcome for each repository, in the same order as `repo_paths`
+    pub fn pull_all(
+        &self,
+        repo_paths: &[PathBuf],
+    ) -> Vec<(PathBuf, Result<PullOutcome, GitError>)> {
+        let repo_paths: Vec<&Path> = repo_paths.iter().map(PathBuf::as_path).collect();
+        let hosts: Vec<Option<String>> = repo_paths
+            .iter()
+            .map(|repo_path| GitRemote::remote_host(repo_path, self.remote_name()))
+            .collect();
+
+        batch::run(&repo_paths, &hosts, &BatchOptions::default(), |repo_path| {
+            self.pull_detailed(repo_path)
+        })
+    }
+
+    /// Push local refs to the configured remote (`origin` by default)
+    ///
+    /// SSH or HTTPS credentials are picked from the remote URL just like `pull`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `refspecs` - Refspecs to push, e.g. `refs/heads/main:refs/heads/main`; prefix with `+` to force
+    ///
+    /// # Errors
+    /// Returns `PushFailed` if the push fails or the remote rejects a ref, e.g. a
+    /// non-fast-forward update
+    pub fn push(&self, repo_path: &Path, refspecs: &[&str]) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.pusher.push(repo_path, refspecs)
+    }
+
+    /// Rebase the current branch onto `upstream_rev`, e.g. `origin/main` after a fetch
+    ///
+    /// Replays the local commits `upstream_rev` lacks on top of it, like the `Rebase` merge
+    /// strategy does for `pull`, but without fetching. On conflict the rebase is aborted and
+    /// `MergeRequired` returned.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `upstream_rev` - Revision to rebase onto
+    ///
+    /// # Returns
+    /// The new tip of the current branch
+    pub fn rebase_onto(&self, repo_path: &Path, upstream_rev: &str) -> Result<git2::Oid, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.rebase_onto(repo_path, upstream_rev)
+    }
+
+    /// Hard-reset a local branch to match `<remote>/<branch>`, discarding local commits and changes
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch to reset
+    /// * `fetch` - Whether to fetch from the remote before resetting
+    pub fn reset_to_remote(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        fetch: bool,
+    ) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        self.puller.reset_to_remote(repo_path, branch_name, fetch)
+    }
+
+    /// Stash uncommitted changes to tracked files, like `git stash push`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `message` - Message describing the stash, or `None` for git's default
+    ///
+    /// # Returns
+    /// The stash commit, or `None` if there was nothing to stash
+    pub fn stash_save(
+        &self,
+        repo_path: &Path,
+        message: Option<&str>,
+    ) -> Result<Option<git2::Oid>, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitStash::stash_save(repo_path, message)
+    }
+
+    /// Apply the most recent stash and drop it, like `git stash pop`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn stash_pop(&self, repo_path: &Path) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitStash::stash_pop(repo_path)
+    }
+
+    /// Move the current branch to `rev`, like `git reset --soft`, `--mixed` or `--hard`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `rev` - Revision to reset to (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `mode` - Whether the index and working tree are reset too
+    /// * `confirm_hard` - Must be set for `ResetMode::Hard`, which discards uncommitted changes
+    ///
+    /// # Errors
+    /// Returns `HardResetNotConfirmed` without touching the repository for an unconfirmed
+    /// hard reset, and `RevisionNotFound` if `rev` doesn't resolve to a commit
+    pub fn reset(
+        &self,
+        repo_path: &Path,
+        rev: &str,
+        mode: ResetMode,
+        confirm_hard: bool,
+    ) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitReset::reset(repo_path, rev, mode, confirm_hard)
+    }
+
+    /// Fetch objects and refs from a bundle file, e.g. for air-gapped transfers
+    ///
+    /// Branches are stored as `refs/remotes/bundle/<branch>`. Requires the `git` command line tool.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `bundle` - Path to the bundle file
+    pub fn fetch_from_bundle(&self, repo_path: &Path, bundle: &Path) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitBundle::fetch_from_bundle(repo_path, bundle)
+    }
+
+    /// Write a bundle file containing `refs` (or all refs when empty). Requires the `git` command line tool.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `refs` - Refs or revision ranges to include, e.g. `main` or `v1.0..main`
+    /// * `dest` - Path of the bundle file to create
+    pub fn create_bundle(
+        &self,
+        repo_path: &Path,
+        refs: &[&str],
+        dest: &Path,
+    ) -> Result<(), GitError> {
+        GitBundle::create_bundle(repo_path, refs, dest)
+    }
+
+    /// Check that the configured remote is reachable and accepts our credentials
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Errors
+    /// Returns `NetworkUnreachable` when the host can't be reached and
+    /// `AuthenticationFailed` when the host rejects the credentials
+    pub fn check_access(&self, repo_path: &Path) -> Result<(), GitError> {
+        self.remote.check_access(repo_path, self.remote_name())
+    }
+
+    /// Get the URL configured for `remote`, or `None` if it has no URL
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote` - Name of the remote, e.g. `origin`
+    pub fn get_remote_url(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+    ) -> Result<Option<String>, GitError> {
+        GitRemote::remote_url(repo_path, remote)
+    }
+
+    /// Point `remote` at `url`, e.g. to switch a repository from SSH to HTTPS
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote` - Name of an existing remote, e.g. `origin`
+    /// * `url` - New fetch URL for the remote
+    pub fn set_remote_url(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        url: &str,
+    ) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitRemote::set_remote_url(repo_path, remote, url)
+    }
+
+    /// Get the URL a branch pulls from, following its configured remote rather than assuming `origin`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch
+    ///
+    /// # Returns
+    /// `None` if the branch has no upstream remote with a URL
+    pub fn upstream_url(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<String>, GitError> {
+        GitRemote::upstream_url(repo_path, branch_name)
+    }
+
+    /// Checkout a branch in the repository, or a tag as a detached HEAD
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the branch or tag to checkout
+    ///
+    /// # Returns
+    /// Whether HEAD is now on a branch or detached
+    ///
+    /// # Errors
+    /// Returns an error if neither a branch nor a tag has the name, or checkout fails
+    pub fn checkout_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<CheckoutTarget, GitError> {
+        self.checkout_branch_with_options(repo_path, branch_name, &CheckoutOptions::default())
+    }
+
+    /// Checkout a branch in the repository, e.g. detached at the remote tip without creating a local branch
     ///
     /// # Arguments
     /// * `repo_path` - Path to the repository
-    /// * `branch_name` - Name of the branch to checkout
+    /// * `branch_name` - Name of the branch or tag to checkout
+    /// * `options` - Options controlling the checkout, e.g. whether a local branch is created
     ///
     /// # Errors
-    /// Returns an error if the branch doesn't exist or checkout fails
-    pub fn checkout_branch(&self, repo_path: &Path, branch_name: &str) -> Result<(), GitError> {
-        GitCheckout::checkout_branch(repo_path, branch_name)
+    /// Returns an error if neither a branch nor a tag has the name, or checkout fails
+    pub fn checkout_branch_with_options(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        options: &CheckoutOptions,
+    ) -> Result<CheckoutTarget, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        let tracking_prefix = self
+            .puller
+            .fetch_config()
+            .tracking_prefix(self.remote_name());
+        GitCheckout::checkout_branch(repo_path, branch_name, &tracking_prefix, options)
+    }
+
+    /// Tag a revision, e.g. a release after pulling
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Name of the tag, e.g. `v1.2.0`
+    /// * `target_rev` - Revision to tag (branch, SHA, `HEAD`, ...)
+    /// * `message` - Message for an annotated tag signed with the repository's identity, or
+    ///   `None` for a lightweight tag
+    pub fn create_tag(
+        &self,
+        repo_path: &Path,
+        name: &str,
+        target_rev: &str,
+        message: Option<&str>,
+    ) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitTag::create_tag(repo_path, name, target_rev, message)
+    }
+
+    /// List the repository's tag names, sorted
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn list_tags(&self, repo_path: &Path) -> Result<Vec<String>, GitError> {
+        GitTag::list_tags(repo_path)
+    }
+
+    /// Attach a git note to a commit, e.g. deploy metadata, without rewriting the commit
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commit` - Commit to annotate
+    /// * `message` - Contents of the note
+    /// * `notes_ref` - Notes ref to store the note under, e.g. `refs/notes/deploys`; `None` for
+    ///   the default `refs/notes/commits` (or `core.notesRef`)
+    /// * `force` - Replace an existing note instead of failing
+    pub fn add_note(
+        &self,
+        repo_path: &Path,
+        commit: git2::Oid,
+        message: &str,
+        notes_ref: Option<&str>,
+        force: bool,
+    ) -> Result<git2::Oid, GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitNotes::add_note(repo_path, commit, message, notes_ref, force)
+    }
+
+    /// Read the git note attached to a commit
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commit` - Commit to read the note of
+    /// * `notes_ref` - Notes ref to read from; `None` for the default `refs/notes/commits`
+    ///   (or `core.notesRef`)
+    ///
+    /// # Returns
+    /// `None` if the commit has no note
+    pub fn read_note(
+        &self,
+        repo_path: &Path,
+        commit: git2::Oid,
+        notes_ref: Option<&str>,
+    ) -> Result<Option<String>, GitError> {
+        GitNotes::read_note(repo_path, commit, notes_ref)
+    }
+
+    /// Commit every change to tracked files, like `git commit -a`
+    ///
+    /// Modifications and deletions of tracked files are staged first; untracked files are
+    /// not added.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `message` - Commit message
+    /// * `signature` - Identity to author and commit as
+    ///
+    /// # Returns
+    /// The id of the new commit
+    pub fn commit_all(
+        &self,
+        repo_path: &Path,
+        message: &str,
+        signature: &Author,
+    ) -> Result<git2::Oid, GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitCommit::commit_all(repo_path, message, signature)
+    }
+
+    /// List the files that differ between two revisions, with added and removed line counts
+    ///
+    /// Renamed files are detected and reported once.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `from` - Older revision (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `to` - Newer revision
+    pub fn diff(&self, repo_path: &Path, from: &str, to: &str) -> Result<Vec<FileDiff>, GitError> {
+        GitDiff::diff(repo_path, from, to)
+    }
+
+    /// Read up to `limit` commits reachable from `start_rev` (HEAD if `None`), newest first
+    ///
+    /// Useful for building a changelog after a pull.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `start_rev` - Revision to walk back from (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `limit` - Maximum number of commits to return
+    pub fn log(
+        &self,
+        repo_path: &Path,
+        start_rev: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        let options = LogOptions {
+            start_rev: start_rev.map(str::to_string),
+            limit: Some(limit),
+            ..LogOptions::default()
+        };
+        GitLog::log(repo_path, &options)
+    }
+
+    /// Read the commit history reachable from HEAD or `options.start_rev`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `options` - Options controlling how history is read, e.g. mailmap resolution
+    pub fn log_with_options(
+        &self,
+        repo_path: &Path,
+        options: &LogOptions,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        GitLog::log(repo_path, options)
+    }
+
+    /// Report which working tree files checking out a branch would add, modify, delete or
+    /// overwrite local changes in, without touching the working tree
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the branch that would be checked out
+    pub fn checkout_dry_run(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Vec<CheckoutChange>, GitError> {
+        let tracking_prefix = self
+            .puller
+            .fetch_config()
+            .tracking_prefix(self.remote_name());
+        GitCheckout::checkout_dry_run(repo_path, branch_name, &tracking_prefix)
+    }
+
+    /// Restore specific paths from a revision without switching branches (`git checkout <rev> -- <paths>`)
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `revspec` - Revision to restore from (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `paths` - Paths or pathspecs to restore
+    pub fn checkout_paths(
+        &self,
+        repo_path: &Path,
+        revspec: &str,
+        paths: &[&str],
+    ) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitCheckout::checkout_paths(repo_path, revspec, paths)
+    }
+
+    /// Checkout a commit with a detached HEAD, e.g. a SHA for a reproducible build
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `rev` - Revision to checkout (SHA, short SHA, tag, `HEAD~1`, ...)
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `rev` doesn't resolve to a commit
+    pub fn checkout_commit(&self, repo_path: &Path, rev: &str) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitCheckout::checkout_commit(repo_path, rev)
+    }
+
+    /// Checkout a commit or tag only after verifying it is signed by one of `allowed_keys`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `target` - Branch, tag or commit to checkout
+    /// * `allowed_keys` - GPG fingerprints/long key ids or SSH `SHA256:` fingerprints to trust
+    ///
+    /// # Errors
+    /// Returns `SignatureVerificationFailed` without touching the working tree if verification fails
+    pub fn checkout_verified(
+        &self,
+        repo_path: &Path,
+        target: &str,
+        allowed_keys: &[&str],
+    ) -> Result<(), GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitCheckout::checkout_verified(repo_path, target, allowed_keys)
+    }
+
+    /// List local branches, remote-tracking branches, or both
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `filter` - Which kinds of branches to list
+    pub fn list_branches(
+        &self,
+        repo_path: &Path,
+        filter: BranchFilter,
+    ) -> Result<Vec<BranchInfo>, GitError> {
+        GitBranch::list_branches(repo_path, filter)
+    }
+
+    /// Create a local branch at `start_point`, or HEAD if `None`, without checking it out
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Name of the new branch, e.g. `feature`
+    /// * `start_point` - Revision the branch starts at (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `force` - Move the branch if it already exists instead of failing
+    pub fn create_branch(
+        &self,
+        repo_path: &Path,
+        name: &str,
+        start_point: Option<&str>,
+        force: bool,
+    ) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitBranch::create_branch(repo_path, name, start_point, force)
+    }
+
+    /// Delete a local branch, refusing the checked-out branch and, unless `force`, unmerged ones
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch, e.g. `feature`
+    /// * `force` - Delete the branch even if it isn't merged into its upstream or HEAD
+    pub fn delete_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        force: bool,
+    ) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitBranch::delete_branch(repo_path, branch_name, force)
+    }
+
+    /// Create a direct ref pointing at `oid`, e.g. to build exact repository states in tests
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Full ref name, e.g. `refs/heads/feature`
+    /// * `oid` - Object the ref points at
+    /// * `force` - Overwrite the ref if it already exists
+    pub fn create_ref(
+        &self,
+        repo_path: &Path,
+        name: &str,
+        oid: git2::Oid,
+        force: bool,
+    ) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitRefs::create_ref(repo_path, name, oid, force)
+    }
+
+    /// Follow a symbolic ref such as `HEAD` to the concrete ref it points at
+    ///
+    /// For example, resolving `HEAD` in a bare mirror gives its default branch.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Ref name to resolve, e.g. `HEAD`
+    pub fn resolve_symbolic_ref(&self, repo_path: &Path, name: &str) -> Result<String, GitError> {
+        GitRefs::resolve_symbolic_ref(repo_path, name)
+    }
+
+    /// Delete a ref by its full name
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Full ref name, e.g. `refs/heads/feature`
+    pub fn delete_ref(&self, repo_path: &Path, name: &str) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitRefs::delete_ref(repo_path, name)
+    }
+
+    /// Cherry-pick `rev` onto the current branch, e.g. to backport a fix
+    ///
+    /// On conflict nothing is committed: the files the commit touches are restored and
+    /// `MergeRequired` is returned.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `rev` - Commit to apply (branch, tag, SHA, `HEAD~1`, ...)
+    ///
+    /// # Returns
+    /// The new commit, or `None` if the current branch already has the commit's changes
+    pub fn cherry_pick(&self, repo_path: &Path, rev: &str) -> Result<Option<git2::Oid>, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitCherryPick::cherry_pick(repo_path, rev)
+    }
+
+    /// Cherry-pick `commits` in order onto the local branch `onto`, e.g. to backport a PR
+    ///
+    /// This is `git rebase --onto` with an explicit commit list. On conflict it stops with
+    /// `TransplantConflict`, reporting the failed commit and how many were applied, and
+    /// leaves the conflicted cherry-pick in place for resolution.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commits` - Commits to apply, oldest first
+    /// * `onto` - Name of the local branch to apply them to
+    ///
+    /// # Returns
+    /// The ids of the new commits, in order
+    pub fn transplant(
+        &self,
+        repo_path: &Path,
+        commits: &[git2::Oid],
+        onto: &str,
+    ) -> Result<Vec<git2::Oid>, GitError> {
+        self.ensure_not_submodule(repo_path)?;
+        let _lock = self.lock_repo(repo_path)?;
+        GitCherryPick::transplant(repo_path, commits, onto, self.conflict_style)
+    }
+
+    /// Describe HEAD relative to the nearest tag, like `git describe --tags --dirty --always`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `options` - Which tags to use and how to format the result
+    pub fn describe(
+        &self,
+        repo_path: &Path,
+        options: &DescribeOptions,
+    ) -> Result<String, GitError> {
+        GitRevision::describe(repo_path, options)
+    }
+
+    /// Get the shortest unambiguous abbreviation of an object id
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `oid` - Object id to abbreviate
+    /// * `min_len` - Minimum abbreviation length; when `None` the repository's `core.abbrev` is used
+    pub fn short_sha(
+        &self,
+        repo_path: &Path,
+        oid: git2::Oid,
+        min_len: Option<usize>,
+    ) -> Result<String, GitError> {
+        GitRevision::short_sha(repo_path, oid, min_len)
+    }
+
+    /// Read the submodules declared in `.gitmodules` without initializing or cloning them
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn parse_gitmodules(&self, repo_path: &Path) -> Result<Vec<GitmoduleEntry>, GitError> {
+        GitSubmodule::parse_gitmodules(repo_path)
+    }
+
+    /// Find the root of the repository containing `path`, so any subdirectory can be passed to other methods
+    ///
+    /// Parent directories are searched the same way the `git` command line tool does.
+    ///
+    /// # Arguments
+    /// * `path` - Any path inside a working tree or git directory
+    ///
+    /// # Returns
+    /// The working tree root, or the repository directory itself for bare repositories
+    pub fn discover_root(path: &Path) -> Result<PathBuf, GitError> {
+        GitRepository::discover_root(path)
+    }
+
+    /// Describe the repository at `repo_path`, including whether it is a submodule
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn repo_info(&self, repo_path: &Path) -> Result<RepoInfo, GitError> {
+        GitRepository::repo_info(repo_path)
+    }
+
+    /// Report the progress of a merge or rebase that stopped partway, e.g. on a conflict
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `None` when no merge or rebase is in progress
+    pub fn operation_progress(&self, repo_path: &Path) -> Result<Option<OpProgress>, GitError> {
+        GitRepository::operation_progress(repo_path)
+    }
+
+    /// Check if the index or working tree has changes that aren't excluded by `options`
+    ///
+    /// Useful as a guard before pulling or checking out, with generated files or logs ignored.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `options` - Which paths and kinds of changes to disregard
+    pub fn is_dirty(&self, repo_path: &Path, options: &DirtyOptions) -> Result<bool, GitError> {
+        GitStatus::is_dirty(repo_path, options)
+    }
+
+    /// Check if the index and working tree match HEAD, untracked files included
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn is_clean(&self, repo_path: &Path) -> Result<bool, GitError> {
+        GitStatus::is_clean(repo_path)
+    }
+
+    /// List files that differ from HEAD in the index or working tree, like `git status`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn status(&self, repo_path: &Path) -> Result<Vec<StatusEntry>, GitError> {
+        GitStatus::status(repo_path)
+    }
+
+    /// Get when the repository last fetched, e.g. to skip refetching a repository another process just updated
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `None` if the repository has never fetched
+    pub fn last_fetch_time(&self, repo_path: &Path) -> Result<Option<SystemTime>, GitError> {
+        GitRepository::last_fetch_time(repo_path)
+    }
+
+    /// Build the commit-graph file so ancestry and merge-base queries stay fast on large repositories
+    ///
+    /// Requires the `git` command line tool.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn write_commit_graph(&self, repo_path: &Path) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitRepository::write_commit_graph(repo_path)
+    }
+
+    /// Get the repository's description, as shown by web front ends like gitweb and cgit
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `None` if the repository has no `description` file
+    pub fn get_description(&self, repo_path: &Path) -> Result<Option<String>, GitError> {
+        GitRepository::description(repo_path)
+    }
+
+    /// Set the repository's description, as shown by web front ends like gitweb and cgit
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `description` - New description; line breaks are collapsed to spaces
+    pub fn set_description(&self, repo_path: &Path, description: &str) -> Result<(), GitError> {
+        let _lock = self.lock_repo(repo_path)?;
+        GitRepository::set_description(repo_path, description)
+    }
+
+    /// Take the repository's advisory lock if a lock timeout is set
+    fn lock_repo(&self, repo_path: &Path) -> Result<Option<RepoLock>, GitError> {
+        self.lock_timeout
+            .map(|timeout| RepoLock::acquire(repo_path, timeout))
+            .transpose()
+    }
+
+    /// Fail with `InsideSubmodule` if submodules are rejected and `repo_path` is one
+    fn ensure_not_submodule(&self, repo_path: &Path) -> Result<(), GitError> {
+        if !self.reject_submodules {
+            return Ok(());
+        }
+
+        let info = GitRepository::repo_info(repo_path)?;
+        match info.superproject {
+            Some(superproject) => Err(GitError::InsideSubmodule {
+                path: repo_path.to_path_buf(),
+                superproject,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl GitClient {
+    /// Pull using an HTTPS token obtained from an async provider, e.g. an OIDC token exchange
+    ///
+    /// git2's credential callbacks are synchronous, so the token is resolved up-front in the
+    /// async context and then handed to the pull, which runs on tokio's blocking thread pool.
+    /// The token must therefore stay valid for the duration of the pull.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    /// * `token_provider` - Produces the token to offer to HTTPS remotes
+    pub async fn pull_with_token_provider<F, Fut>(
+        &self,
+        repo_path: &Path,
+        token_provider: F,
+    ) -> Result<(), GitError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String, GitError>>,
+    {
+        let token = token_provider().await?;
+
+        let mut client = self.clone();
+        client.set_https_token(Some(token));
+        let repo_path = repo_path.to_path_buf();
+        Self::run_blocking(move || client.pull(&repo_path)).await
+    }
+
+    /// Async version of `pull`, run on tokio's blocking thread pool
+    pub async fn pull_async(&self, repo_path: &Path) -> Result<(), GitError> {
+        let client = self.clone();
+        let repo_path = repo_path.to_path_buf();
+        Self::run_blocking(move || client.pull(&repo_path)).await
+    }
+
+    /// Async version of `pull_with_strategy`, run on tokio's blocking thread pool
+    pub async fn pull_with_strategy_async(
+        &self,
+        repo_path: &Path,
+        strategy: MergeStrategy,
+    ) -> Result<PullOutcome, GitError> {
+        let client = self.clone();
+        let repo_path = repo_path.to_path_buf();
+        Self::run_blocking(move || client.pull_with_strategy(&repo_path, strategy)).await
+    }
+
+    /// Async version of `fetch`, run on tokio's blocking thread pool
+    pub async fn fetch_async(&self, repo_path: &Path) -> Result<(), GitError> {
+        let client = self.clone();
+        let repo_path = repo_path.to_path_buf();
+        Self::run_blocking(move || client.fetch(&repo_path)).await
+    }
+
+    /// Async version of `clone_repo`, run on tokio's blocking thread pool
+    pub async fn clone_async(&self, url: &str, dest: &Path) -> Result<(), GitError> {
+        let client = self.clone();
+        let url = url.to_string();
+        let dest = dest.to_path_buf();
+        Self::run_blocking(move || client.clone_repo(&url, &dest)).await
+    }
+
+    /// Async version of `push`, run on tokio's blocking thread pool
+    pub async fn push_async(&self, repo_path: &Path, refspecs: &[&str]) -> Result<(), GitError> {
+        let client = self.clone();
+        let repo_path = repo_path.to_path_buf();
+        let refspecs: Vec<String> = refspecs.iter().map(|refspec| refspec.to_string()).collect();
+        Self::run_blocking(move || {
+            let refspecs: Vec<&str> = refspecs.iter().map(String::as_str).collect();
+            client.push(&repo_path, &refspecs)
+        })
+        .await
+    }
+
+    /// Async version of `checkout_branch`, run on tokio's blocking thread pool
+    pub async fn checkout_branch_async(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<CheckoutTarget, GitError> {
+        let client = self.clone();
+        let repo_path = repo_path.to_path_buf();
+        let branch_name = branch_name.to_string();
+        Self::run_blocking(move || client.checkout_branch(&repo_path, &branch_name)).await
+    }
+
+    /// Run a blocking git operation on tokio's blocking thread pool
+    async fn run_blocking<T, F>(operation: F) -> Result<T, GitError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, GitError> + Send + 'static,
+    {
+        match tokio::task::spawn_blocking(operation).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(GitError::Git(git2::Error::from_str(&format!(
+                "Blocking git task was cancelled: {e}"
+            )))),
+        }
     }
 }
 
@@ -52,3 +1450,132 @@ impl Default for GitClient {
     }
 }
 
+#[cfg(test)]
+mod sync_tests {
+    use super::*;
+    use crate::test_support::{commit_file, upstream_and_clone};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pull_all_reports_each_repository() {
+        let current_dir = TempDir::new().unwrap();
+        let behind_dir = TempDir::new().unwrap();
+        let (_upstream, current) = upstream_and_clone(current_dir.path());
+        let (upstream, behind) = upstream_and_clone(behind_dir.path());
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let missing = behind_dir.path().join("missing");
+        let client = GitClient::with_ssh_config(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+        let repo_paths = vec![
+            current.workdir().unwrap().to_path_buf(),
+            behind.workdir().unwrap().to_path_buf(),
+            missing.clone(),
+        ];
+
+        let results = client.pull_all(&repo_paths);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, repo_paths[0]);
+        assert!(matches!(results[0].1, Ok(PullOutcome::UpToDate)));
+        assert!(matches!(
+            results[1].1,
+            Ok(PullOutcome::FastForwarded { new, .. }) if new == new_commit
+        ));
+        assert_eq!(results[2].0, missing);
+        assert!(results[2].1.is_err());
+        assert_eq!(behind.head().unwrap().target(), Some(new_commit));
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, upstream_and_clone};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_pull_async() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let client = GitClient::with_ssh_config(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        client.pull_async(local.workdir().unwrap()).await.unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+    }
+
+    #[tokio::test]
+    async fn test_clone_async() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, _local) = upstream_and_clone(temp_dir.path());
+        let dest = temp_dir.path().join("copy");
+        let client = GitClient::with_ssh_config(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        client
+            .clone_async(upstream.workdir().unwrap().to_str().unwrap(), &dest)
+            .await
+            .unwrap();
+
+        let copy = git2::Repository::open(&dest).unwrap();
+        assert_eq!(
+            copy.head().unwrap().target(),
+            upstream.head().unwrap().target()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pull_with_token_provider() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let client = GitClient::with_ssh_config(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        client
+            .pull_with_token_provider(local.workdir().unwrap(), || async {
+                Ok("minted-token".to_string())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+    }
+
+    #[tokio::test]
+    async fn test_pull_with_failing_token_provider() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_upstream, local) = upstream_and_clone(temp_dir.path());
+        let client = GitClient::with_ssh_config(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        let result = client
+            .pull_with_token_provider(local.workdir().unwrap(), || async {
+                Err(GitError::Git(git2::Error::from_str(
+                    "token exchange failed",
+                )))
+            })
+            .await;
+
+        assert!(matches!(result, Err(GitError::Git(_))));
+    }
+}
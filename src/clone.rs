@@ -0,0 +1,498 @@
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{IndexEntryExtendedFlag, IndexEntryFlag, Pathspec, PathspecFlags, Repository};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::auth::{self, SshConfig, TokenAuth};
+use crate::error::GitError;
+use crate::progress::{self, Progress, ProgressCallback};
+use crate::remote;
+use crate::retry::RetryPolicy;
+
+/// Clone operations for Git repositories
+#[derive(Clone)]
+pub struct GitCloner {
+    ssh_config: SshConfig,
+    /// Token offered to HTTPS remotes before the credential helper and environment
+    https_token: Option<String>,
+    /// Environment variables tried for HTTPS tokens after the credential helper
+    token_auth: TokenAuth,
+    /// Proxy for HTTP(S) remotes, `None` to detect it from git config and the environment
+    proxy: Option<String>,
+    /// Number of commits of history to clone, or `None` for full history
+    depth: Option<u32>,
+    /// Called with transfer progress while cloning
+    progress: Option<ProgressCallback>,
+    /// Minimum time between progress updates, `None` to report every update
+    progress_interval: Option<Duration>,
+    /// Retries for clones that fail with transient network errors
+    retry_policy: RetryPolicy,
+    /// Clone without a working tree
+    bare: bool,
+    /// Clone bare and mirror every ref of the remote
+    mirror: bool,
+}
+
+impl GitCloner {
+    /// Create a new GitCloner with the provided SSH configuration
+    pub fn new(ssh_config: SshConfig) -> Self {
+        Self {
+            ssh_config,
+            https_token: None,
+            token_auth: TokenAuth::default(),
+            proxy: None,
+            depth: None,
+            progress: None,
+            progress_interval: None,
+            retry_policy: RetryPolicy::default(),
+            bare: false,
+            mirror: false,
+        }
+    }
+
+    /// Clone only the latest `depth` commits, like `git clone --depth`; `None` clones full history
+    ///
+    /// Shallow clones need an HTTP(S) remote, see `FetchConfig::set_depth`.
+    pub fn set_depth(&mut self, depth: Option<u32>) {
+        self.depth = depth;
+    }
+
+    /// Offer `https_token` to HTTPS remotes before the credential helper and environment tokens
+    pub fn set_https_token(&mut self, https_token: Option<String>) {
+        self.https_token = https_token;
+    }
+
+    /// Set the environment variables tried for HTTPS tokens; defaults to GitHub's
+    pub fn set_token_auth(&mut self, token_auth: TokenAuth) {
+        self.token_auth = token_auth;
+    }
+
+    /// Connect to HTTP(S) remotes through `proxy`; `None` detects it from git config and the environment
+    pub fn set_proxy(&mut self, proxy: Option<String>) {
+        self.proxy = proxy;
+    }
+
+    /// Call `callback` with transfer progress while cloning, replacing any earlier callback
+    pub fn set_progress_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+    }
+
+    /// Stop reporting transfer progress
+    pub fn clear_progress_callback(&mut self) {
+        self.progress = None;
+    }
+
+    /// Report progress at most once per `interval`, plus once when the transfer completes
+    ///
+    /// `None` reports every update libgit2 sends, which can be thousands per second.
+    pub fn set_progress_interval(&mut self, interval: Option<Duration>) {
+        self.progress_interval = interval;
+    }
+
+    /// Retry clones that fail with transient network errors under `retry_policy`
+    ///
+    /// Defaults to a single attempt. Whatever a failed attempt left in `dest` is removed
+    /// before each retry; a `dest` directory that existed beforehand is emptied, not removed.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Clone without a working tree, like `git clone --bare`
+    pub fn set_bare(&mut self, bare: bool) {
+        self.bare = bare;
+    }
+
+    /// Clone bare with every remote ref copied to the same name, like `git clone --mirror`
+    ///
+    /// The remote fetches `+refs/*:refs/*` and is marked `remote.<name>.mirror`, so pulling
+    /// the mirror later updates all of its refs.
+    pub fn set_mirror(&mut self, mirror: bool) {
+        self.mirror = mirror;
+    }
+
+    /// Clone a repository and check out its default branch
+    ///
+    /// Credentials are chosen from the URL the same way `pull` chooses them. If the clone
+    /// fails, `dest` is removed.
+    ///
+    /// # Arguments
+    /// * `url` - URL of the repository to clone
+    /// * `dest` - Directory to clone into; must not exist or be empty
+    pub fn clone_repo(&self, url: &str, dest: &Path) -> Result<(), GitError> {
+        let dest_existed = dest.exists();
+        let result = self.clone_with_checkout(url, dest, &[]).map(|_| ());
+
+        if result.is_err() && !dest_existed {
+            let _ = fs::remove_dir_all(dest);
+        }
+
+        result
+    }
+
+    /// Clone a repository, checking out only the paths matching `patterns`
+    ///
+    /// The full history is fetched, but only matching files are written to the working tree.
+    /// The remaining index entries are marked skip-worktree and the patterns are written to
+    /// `.git/info/sparse-checkout` with `core.sparseCheckout` enabled, so the git command line
+    /// treats the clone as a sparse checkout too. If any step fails, `dest` is removed.
+    ///
+    /// # Arguments
+    /// * `url` - URL of the repository to clone
+    /// * `dest` - Directory to clone into; must not exist or be empty
+    /// * `patterns` - Pathspecs to check out, e.g. `services/api` or `docs/*.md`
+    pub fn clone_sparse(&self, url: &str, dest: &Path, patterns: &[&str]) -> Result<(), GitError> {
+        let clone_error = |e: git2::Error| GitError::CloneFailed {
+            url: url.to_string(),
+            dest: dest.to_path_buf(),
+            source: e,
+        };
+
+        let dest_existed = dest.exists();
+        let result = self
+            .clone_with_checkout(url, dest, patterns)
+            .and_then(|repo| Self::configure_sparse_checkout(&repo, patterns).map_err(clone_error));
+
+        if result.is_err() && !dest_existed {
+            let _ = fs::remove_dir_all(dest);
+        }
+
+        result
+    }
+
+    /// Clone `url` into `dest`, checking out only paths matching `patterns` (everything if empty)
+    ///
+    /// Transient failures are retried under the retry policy.
+    fn clone_with_checkout(
+        &self,
+        url: &str,
+        dest: &Path,
+        patterns: &[&str],
+    ) -> Result<Repository, GitError> {
+        let callbacks = || {
+            auth::remote_callbacks(
+                &self.ssh_config,
+                url,
+                self.https_token.as_deref(),
+                &self.token_auth,
+            )
+        };
+        // Set up credentials once up front, so a configuration error keeps its type
+        let mut first_callbacks = Some(callbacks()?);
+        let dest_existed = dest.exists();
+
+        self.retry_policy
+            .run(|| {
+                let callbacks = match first_callbacks.take() {
+                    Some(callbacks) => callbacks,
+                    None => {
+                        // Clear what the failed attempt left, keeping a directory the caller made
+                        if dest_existed {
+                            clear_dir(dest);
+                        } else {
+                            let _ = fs::remove_dir_all(dest);
+                        }
+                        callbacks().map_err(|e| git2::Error::from_str(&e.to_string()))?
+                    }
+                };
+                self.clone_attempt(callbacks, url, dest, patterns)
+            })
+            .map_err(|e| GitError::CloneFailed {
+                url: url.to_string(),
+                dest: dest.to_path_buf(),
+                source: e,
+            })
+    }
+
+    /// Make a single attempt at cloning `url` into `dest` with `callbacks`
+    fn clone_attempt(
+        &self,
+        mut callbacks: git2::RemoteCallbacks<'static>,
+        url: &str,
+        dest: &Path,
+        patterns: &[&str],
+    ) -> Result<Repository, git2::Error> {
+        progress::report_transfer(
+            &mut callbacks,
+            self.progress.as_ref(),
+            self.progress_interval,
+            None,
+        );
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        fetch_options.proxy_options(remote::proxy_options(self.proxy.as_deref()));
+        if let Some(depth) = self.depth {
+            fetch_options.depth(i32::try_from(depth).unwrap_or(i32::MAX));
+        }
+
+        let mut checkout_builder = CheckoutBuilder::new();
+        for pattern in patterns {
+            checkout_builder.path(pattern);
+        }
+
+        let mut builder = RepoBuilder::new();
+        builder
+            .bare(self.bare || self.mirror)
+            .fetch_options(fetch_options)
+            .with_checkout(checkout_builder);
+        if self.mirror {
+            builder.remote_create(|repo, name, url| {
+                let remote = repo.remote_with_fetch(name, url, "+refs/*:refs/*")?;
+                repo.config()?
+                    .set_bool(&format!("remote.{name}.mirror"), true)?;
+                Ok(remote)
+            });
+        }
+        builder.clone(url, dest)
+    }
+
+    /// Mark paths outside `patterns` as skip-worktree and record the sparse-checkout patterns
+    fn configure_sparse_checkout(repo: &Repository, patterns: &[&str]) -> Result<(), git2::Error> {
+        let pathspec = Pathspec::new(patterns.iter())?;
+        let mut index = repo.index()?;
+
+        // A path-limited checkout only indexes the paths it wrote, so index the whole tree
+        index.read_tree(&repo.head()?.peel_to_tree()?)?;
+
+        let skipped: Vec<_> = index
+            .iter()
+            .filter(|entry| {
+                let path = String::from_utf8_lossy(&entry.path).into_owned();
+                !pathspec.matches_path(Path::new(&path), PathspecFlags::DEFAULT)
+            })
+            .collect();
+
+        for mut entry in skipped {
+            entry.flags |= IndexEntryFlag::EXTENDED.bits();
+            entry.flags_extended |= IndexEntryExtendedFlag::SKIP_WORKTREE.bits();
+            index.add(&entry)?;
+        }
+        index.write()?;
+
+        let info_dir = repo.path().join("info");
+        fs::create_dir_all(&info_dir)
+            .and_then(|_| fs::write(info_dir.join("sparse-checkout"), patterns.join("\n") + "\n"))
+            .map_err(|e| git2::Error::from_str(&format!("Failed to write sparse-checkout: {e}")))?;
+        repo.config()?.set_bool("core.sparseCheckout", true)?;
+
+        Ok(())
+    }
+}
+
+/// Remove everything inside the directory `dir`, leaving it empty
+///
+/// Clones only start in an empty directory, so anything here was left by a failed attempt.
+fn clear_dir(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let _ = match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => fs::remove_dir_all(&path),
+            _ => fs::remove_file(&path),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command;
+    use crate::test_support::{commit_file, init_repo, GitHttpServer};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_clone_sparse_checks_out_matching_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream_path = temp_dir.path().join("upstream");
+        let upstream = init_repo(&upstream_path);
+        commit_file(&upstream, "services/api/main.rs", "api\n", "Add api");
+        commit_file(&upstream, "services/web/main.rs", "web\n", "Add web");
+        commit_file(&upstream, "README.md", "readme\n", "Add readme");
+        let dest = temp_dir.path().join("sparse");
+        let cloner = GitCloner::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        cloner
+            .clone_sparse(upstream_path.to_str().unwrap(), &dest, &["services/api"])
+            .unwrap();
+
+        assert!(dest.join("services/api/main.rs").exists());
+        assert!(!dest.join("services/web/main.rs").exists());
+        assert!(!dest.join("README.md").exists());
+        let status = command::run_git_checked(&dest, &["status", "--porcelain"]).unwrap();
+        assert_eq!(status, "");
+    }
+
+    #[test]
+    fn test_clear_dir_empties_existing_dest() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("existing");
+        fs::create_dir_all(dest.join(".git/objects")).unwrap();
+        fs::write(dest.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(dest.join("partial.txt"), "left by a failed attempt\n").unwrap();
+
+        clear_dir(&dest);
+
+        assert!(dest.is_dir());
+        assert_eq!(fs::read_dir(&dest).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_clone_sparse_removes_dest_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("sparse");
+        let cloner = GitCloner::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        let result = cloner.clone_sparse(
+            temp_dir.path().join("missing").to_str().unwrap(),
+            &dest,
+            &["src"],
+        );
+
+        assert!(matches!(result, Err(GitError::CloneFailed { .. })));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_mirror_clone_copies_all_refs_and_pull_updates_them() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream_path = temp_dir.path().join("upstream");
+        let upstream = init_repo(&upstream_path);
+        let main = commit_file(&upstream, "README.md", "readme\n", "Initial commit");
+        let head = upstream.find_commit(main).unwrap();
+        upstream.branch("feature", &head, false).unwrap();
+        upstream
+            .tag_lightweight("v1.0.0", head.as_object(), false)
+            .unwrap();
+        let dest = temp_dir.path().join("mirror.git");
+        let ssh_config = SshConfig::new(vec![], PathBuf::from("/nonexistent/known_hosts"), false);
+        let mut cloner = GitCloner::new(ssh_config.clone());
+        cloner.set_mirror(true);
+
+        cloner
+            .clone_repo(upstream_path.to_str().unwrap(), &dest)
+            .unwrap();
+
+        let mirror = Repository::open(&dest).unwrap();
+        assert!(mirror.is_bare());
+        assert_eq!(mirror.refname_to_id("refs/heads/main").unwrap(), main);
+        assert_eq!(mirror.refname_to_id("refs/heads/feature").unwrap(), main);
+        assert_eq!(mirror.refname_to_id("refs/tags/v1.0.0").unwrap(), main);
+        assert!(mirror.find_reference("refs/remotes/origin/main").is_err());
+        assert!(mirror
+            .config()
+            .unwrap()
+            .get_bool("remote.origin.mirror")
+            .unwrap());
+
+        upstream.set_head("refs/heads/feature").unwrap();
+        let feature = commit_file(&upstream, "feature.txt", "feature\n", "Feature work");
+        crate::pull::GitPuller::new(ssh_config).pull(&dest).unwrap();
+
+        assert_eq!(mirror.refname_to_id("refs/heads/feature").unwrap(), feature);
+    }
+
+    #[test]
+    fn test_clone_bare_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream_path = temp_dir.path().join("upstream");
+        let upstream = init_repo(&upstream_path);
+        let commit = commit_file(&upstream, "README.md", "readme\n", "Initial commit");
+        let bare_path = temp_dir.path().join("bare.git");
+        RepoBuilder::new()
+            .bare(true)
+            .clone(upstream_path.to_str().unwrap(), &bare_path)
+            .unwrap();
+        let dest = temp_dir.path().join("clone");
+        let cloner = GitCloner::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+
+        cloner
+            .clone_repo(bare_path.to_str().unwrap(), &dest)
+            .unwrap();
+
+        let repo = Repository::open(&dest).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(commit));
+        assert_eq!(
+            std::fs::read_to_string(dest.join("README.md")).unwrap(),
+            "readme\n"
+        );
+    }
+
+    #[test]
+    fn test_clone_reports_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream_path = temp_dir.path().join("upstream");
+        let upstream = init_repo(&upstream_path);
+        commit_file(&upstream, "file.txt", "one\n", "First");
+        commit_file(&upstream, "file.txt", "two\n", "Second");
+        let mut cloner = GitCloner::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = reports.clone();
+        cloner.set_progress_callback(move |progress| recorded.lock().unwrap().push(progress));
+
+        // A file:// URL goes through the local transport instead of copying the object database
+        let url = format!("file://{}", upstream_path.display());
+
+        cloner
+            .clone_repo(&url, &temp_dir.path().join("copy"))
+            .unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert!(!reports.is_empty());
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].received_objects <= pair[1].received_objects));
+        let last = reports.last().unwrap();
+        assert!(last.received_objects > 0);
+        assert_eq!(last.received_objects, last.total_objects);
+    }
+
+    #[test]
+    fn test_clone_with_depth_limits_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream_path = temp_dir.path().join("upstream");
+        let upstream = init_repo(&upstream_path);
+        commit_file(&upstream, "file.txt", "one\n", "First");
+        commit_file(&upstream, "file.txt", "two\n", "Second");
+        let head = commit_file(&upstream, "file.txt", "three\n", "Third");
+        let dest = temp_dir.path().join("shallow");
+        let mut cloner = GitCloner::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ));
+        cloner.set_depth(Some(1));
+
+        let server = GitHttpServer::serve(temp_dir.path());
+
+        cloner.clone_repo(&server.url("upstream"), &dest).unwrap();
+
+        let repo = Repository::open(&dest).unwrap();
+        let mut revwalk = repo.revwalk().unwrap();
+        revwalk.push_head().unwrap();
+        assert!(repo.is_shallow());
+        assert_eq!(revwalk.map(Result::unwrap).collect::<Vec<_>>(), vec![head]);
+    }
+}
@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::error::GitError;
+
+/// Run the `git` command line tool in `repo_path` for operations libgit2 doesn't support
+///
+/// The output is returned regardless of exit status; only failing to launch `git` is an error.
+pub(crate) fn run_git(repo_path: &Path, args: &[&str]) -> Result<Output, GitError> {
+    Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+        .map_err(|e| GitError::CommandFailed {
+            command: format!("git {}", args.join(" ")),
+            path: repo_path.to_path_buf(),
+            stderr: e.to_string(),
+        })
+}
+
+/// Run `git` in `repo_path` and return its stdout, failing if it exits unsuccessfully
+pub(crate) fn run_git_checked(repo_path: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = run_git(repo_path, args)?;
+
+    if !output.status.success() {
+        return Err(GitError::CommandFailed {
+            command: format!("git {}", args.join(" ")),
+            path: repo_path.to_path_buf(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
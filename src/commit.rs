@@ -0,0 +1,85 @@
+use git2::{Commit, Oid, Repository, Signature};
+use std::path::Path;
+
+use crate::author::Author;
+use crate::error::GitError;
+
+/// Commit operations for Git repositories
+pub struct GitCommit;
+
+impl GitCommit {
+    /// Stage every modification and deletion of tracked files and commit, like `git commit -a`
+    ///
+    /// Untracked files are left alone. `signature` is used as both author and committer.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `message` - Commit message
+    /// * `signature` - Identity to author and commit as
+    ///
+    /// # Returns
+    /// The id of the new commit
+    pub fn commit_all(
+        repo_path: &Path,
+        message: &str,
+        signature: &Author,
+    ) -> Result<Oid, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut index = repo.index()?;
+        index.update_all(["*"], None)?;
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+
+        let parents: Vec<Commit> = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit()?],
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Vec::new(),
+            Err(e) => return Err(GitError::Git(e)),
+        };
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+
+        let signature = Signature::try_from(signature)?;
+        Ok(repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parent_refs,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_all_stages_tracked_changes_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "modified.txt", "one\n", "Add modified");
+        let parent = commit_file(&repo, "deleted.txt", "one\n", "Add deleted");
+        std::fs::write(temp_dir.path().join("modified.txt"), "two\n").unwrap();
+        std::fs::remove_file(temp_dir.path().join("deleted.txt")).unwrap();
+        std::fs::write(temp_dir.path().join("untracked.txt"), "new\n").unwrap();
+        let author = Author::now("Commit Author", "author@example.com");
+
+        let oid = GitCommit::commit_all(temp_dir.path(), "Update everything", &author).unwrap();
+
+        let commit = repo.find_commit(oid).unwrap();
+        let tree = commit.tree().unwrap();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), vec![parent]);
+        assert_eq!(commit.author().name(), Some("Commit Author"));
+        assert_eq!(commit.message(), Some("Update everything"));
+        assert!(tree.get_name("modified.txt").is_some());
+        assert!(tree.get_name("deleted.txt").is_none());
+        assert!(tree.get_name("untracked.txt").is_none());
+        assert_eq!(repo.head().unwrap().target(), Some(oid));
+    }
+}
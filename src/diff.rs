@@ -0,0 +1,155 @@
+use git2::{Delta, DiffFindOptions, Patch, Repository, Tree};
+use std::path::{Path, PathBuf};
+
+use crate::error::GitError;
+
+/// How a file changed between two revisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The file only exists in the newer revision
+    Added,
+    /// The file's contents or type changed
+    Modified,
+    /// The file only exists in the older revision
+    Deleted,
+    /// The file was moved, possibly with changes
+    Renamed,
+}
+
+/// A file that differs between two revisions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// Path in the older revision, `None` if the file was added
+    pub old_path: Option<PathBuf>,
+    /// Path in the newer revision, `None` if the file was deleted
+    pub new_path: Option<PathBuf>,
+    /// How the file changed
+    pub kind: ChangeKind,
+    /// Lines added; 0 for binary files
+    pub additions: usize,
+    /// Lines removed; 0 for binary files
+    pub deletions: usize,
+}
+
+/// Diff operations for Git repositories
+pub struct GitDiff;
+
+impl GitDiff {
+    /// List the files that differ between two revisions, like `git diff --numstat -M from to`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `from` - Older revision (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `to` - Newer revision
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `from` or `to` doesn't resolve to a tree
+    pub fn diff(repo_path: &Path, from: &str, to: &str) -> Result<Vec<FileDiff>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let from_tree = resolve_tree(&repo, repo_path, from)?;
+        let to_tree = resolve_tree(&repo, repo_path, to)?;
+        let mut diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+        let mut files = Vec::new();
+        for (index, delta) in diff.deltas().enumerate() {
+            let kind = match delta.status() {
+                Delta::Added => ChangeKind::Added,
+                Delta::Deleted => ChangeKind::Deleted,
+                Delta::Renamed => ChangeKind::Renamed,
+                _ => ChangeKind::Modified,
+            };
+            let (_, additions, deletions) = match Patch::from_diff(&diff, index)? {
+                Some(patch) => patch.line_stats()?,
+                None => (0, 0, 0),
+            };
+
+            files.push(FileDiff {
+                old_path: (kind != ChangeKind::Added)
+                    .then(|| delta.old_file().path().map(Path::to_path_buf))
+                    .flatten(),
+                new_path: (kind != ChangeKind::Deleted)
+                    .then(|| delta.new_file().path().map(Path::to_path_buf))
+                    .flatten(),
+                kind,
+                additions,
+                deletions,
+            });
+        }
+
+        Ok(files)
+    }
+}
+
+/// Resolve `rev` to the tree it points at
+fn resolve_tree<'repo>(
+    repo: &'repo Repository,
+    repo_path: &Path,
+    rev: &str,
+) -> Result<Tree<'repo>, GitError> {
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| GitError::RevisionNotFound {
+            rev: rev.to_string(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_counts_lines_per_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\ntwo\nthree\n", "First");
+        commit_file(&repo, "removed.txt", "gone\n", "Add file to remove");
+        fs::remove_file(temp_dir.path().join("removed.txt")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("removed.txt")).unwrap();
+        index.write().unwrap();
+        commit_file(&repo, "file.txt", "one\n2\nthree\nfour\n", "Second");
+
+        let files = GitDiff::diff(temp_dir.path(), "HEAD~1", "HEAD").unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                FileDiff {
+                    old_path: Some(PathBuf::from("file.txt")),
+                    new_path: Some(PathBuf::from("file.txt")),
+                    kind: ChangeKind::Modified,
+                    additions: 2,
+                    deletions: 1,
+                },
+                FileDiff {
+                    old_path: Some(PathBuf::from("removed.txt")),
+                    new_path: None,
+                    kind: ChangeKind::Deleted,
+                    additions: 0,
+                    deletions: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_unknown_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "First");
+
+        let result = GitDiff::diff(temp_dir.path(), "missing", "HEAD");
+
+        assert!(matches!(result, Err(GitError::RevisionNotFound { rev, .. }) if rev == "missing"));
+    }
+}
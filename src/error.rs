@@ -15,9 +15,28 @@ pub enum GitError {
         source: git2::Error,
     },
 
-    #[error("Failed to pull repository at {path}: {source}")]
+    /// A pull or fetch failed; `url` is the remote's URL when the failure involved it
+    #[error("Failed to pull repository at {path}{}: {source}", from_url(url))]
     PullFailed {
         path: PathBuf,
+        url: Option<String>,
+        #[source]
+        source: git2::Error,
+    },
+
+    /// A push failed; `url` is the remote's URL when the failure involved it
+    #[error("Failed to push repository at {path}{}: {source}", to_url(url))]
+    PushFailed {
+        path: PathBuf,
+        url: Option<String>,
+        #[source]
+        source: git2::Error,
+    },
+
+    #[error("Failed to clone {url} into {dest}: {source}")]
+    CloneFailed {
+        url: String,
+        dest: PathBuf,
         #[source]
         source: git2::Error,
     },
@@ -25,8 +44,80 @@ pub enum GitError {
     #[error("Invalid branch for repository at {0}")]
     InvalidBranch(PathBuf),
 
-    #[error("Manual merge required for repository at {0}")]
-    MergeRequired(PathBuf),
+    /// HEAD names a branch with no commits, and the remote has no branch to create it from
+    #[error("Current branch of repository at {0} has no commits")]
+    UnbornBranch(PathBuf),
+
+    /// The local branch and the fetched remote branch have diverged
+    ///
+    /// The fetch has already completed, so `remote` is available locally for a manual
+    /// merge, reset, or to record the divergence. `cherry_pick` also returns it when the
+    /// picked commit, then `remote`, conflicts with HEAD.
+    #[error("Manual merge required for repository at {path}: local {local} and remote {remote} have diverged")]
+    MergeRequired {
+        path: PathBuf,
+        local: git2::Oid,
+        remote: git2::Oid,
+    },
+
+    /// A cherry-pick in a series conflicted
+    ///
+    /// `commit` is at `index` (starting at 0) in the series of `total` commits. `applied`
+    /// commits were committed before it; commits that were already on the branch aren't
+    /// counted. The conflicted cherry-pick is left in the index and working tree.
+    #[error("Cherry-picking {commit} ({} of {total}) conflicted at {path}", index + 1)]
+    TransplantConflict {
+        path: PathBuf,
+        commit: git2::Oid,
+        index: usize,
+        applied: usize,
+        total: usize,
+    },
+
+    /// An operation needed history a shallow repository doesn't have
+    ///
+    /// More history has to be fetched (or the repository unshallowed) before retrying.
+    #[error("{operation} at {path} needs history beyond the shallow boundary: {source}")]
+    ShallowBoundary {
+        operation: String,
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
+
+    /// Another process held the repository's advisory lock for longer than the lock timeout
+    #[error("Repository at {path} is locked by another operation ({lock_path} held for over {timeout:?})")]
+    RepoLocked {
+        path: PathBuf,
+        lock_path: PathBuf,
+        timeout: std::time::Duration,
+    },
+
+    /// Updating the working tree would overwrite uncommitted local changes
+    #[error("Local changes at {path} would be overwritten: {source}")]
+    DirtyWorkingTree {
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
+
+    /// A transfer was aborted because its cancel flag was set
+    #[error("Operation on repository at {path} was cancelled")]
+    Cancelled { path: PathBuf },
+
+    #[error("Branch {branch} is checked out at {path}")]
+    BranchInUse { branch: String, path: PathBuf },
+
+    #[error("Branch {branch} at {path} is not fully merged")]
+    BranchNotMerged { branch: String, path: PathBuf },
+
+    #[error("Revision {rev} not found at {path}: {source}")]
+    RevisionNotFound {
+        rev: String,
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
 
     #[error("Failed to checkout branch {branch} at {path}: {source}")]
     CheckoutFailed {
@@ -36,6 +127,77 @@ pub enum GitError {
         source: git2::Error,
     },
 
+    #[error("Failed to reset to {target} at {path}: {source}")]
+    ResetFailed {
+        target: String,
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
+
+    /// A pull with autostash couldn't reapply the stashed changes; they stay in the stash
+    #[error("Failed to reapply autostashed changes at {path}: {source}")]
+    AutostashPopFailed {
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
+
+    #[error("Hard reset of {path} was not confirmed")]
+    HardResetNotConfirmed { path: PathBuf },
+
+    #[error("Repository at {path} is a submodule of {superproject}")]
+    InsideSubmodule {
+        path: PathBuf,
+        superproject: PathBuf,
+    },
+
+    #[error("Failed to update submodule {name} of {path}: {source}")]
+    SubmoduleUpdateFailed {
+        name: String,
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
+
+    #[error("Network unreachable for remote {url}: {source}")]
+    NetworkUnreachable {
+        url: String,
+        #[source]
+        source: git2::Error,
+    },
+
+    #[error("Authentication failed for remote {url}: {source}")]
+    AuthenticationFailed {
+        url: String,
+        #[source]
+        source: git2::Error,
+    },
+
+    #[error("Signature verification failed for {target} at {path}: {reason}")]
+    SignatureVerificationFailed {
+        target: String,
+        path: PathBuf,
+        reason: String,
+    },
+
+    #[error("Command `{command}` failed at {path}: {stderr}")]
+    CommandFailed {
+        command: String,
+        path: PathBuf,
+        stderr: String,
+    },
+
+    #[error("Invalid remote URL {url}: {reason}")]
+    InvalidRemoteUrl { url: String, reason: String },
+
+    #[error("I/O error at {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("Git operation failed: {0}")]
     Git(#[from] git2::Error),
 }
@@ -81,15 +243,95 @@ impl GitError {
                     path.display()
                 )
             }
-            GitError::PullFailed { path, .. } => {
+            GitError::PullFailed { path, url, .. } => {
+                format!(
+                    "Failed to pull updates for repository at {}{}. Check your SSH keys and network connection.",
+                    path.display(),
+                    from_url(url)
+                )
+            }
+            GitError::PushFailed { path, url, .. } => {
+                format!(
+                    "Failed to push repository at {}{}. Pull first if the remote has new commits, and check your credentials.",
+                    path.display(),
+                    to_url(url)
+                )
+            }
+            GitError::MergeRequired {
+                path,
+                local,
+                remote,
+            } => {
+                format!(
+                    "Manual merge required for repository at {}: local {} and remote {} have diverged. Merge or reset manually.",
+                    path.display(),
+                    local,
+                    remote
+                )
+            }
+            GitError::CloneFailed { url, dest, .. } => {
+                format!(
+                    "Failed to clone {url} into {}. Check the URL, your credentials and that the destination is empty.",
+                    dest.display()
+                )
+            }
+            GitError::TransplantConflict {
+                path,
+                commit,
+                index,
+                total,
+                ..
+            } => {
                 format!(
-                    "Failed to pull updates for repository at {}. Check your SSH keys and network connection.",
+                    "Cherry-picking {commit} ({} of {total}) conflicted at {}. Resolve the conflicts and commit, or run `git cherry-pick --abort`.",
+                    index + 1,
                     path.display()
                 )
             }
-            GitError::MergeRequired(path) => {
+            GitError::ShallowBoundary {
+                operation, path, ..
+            } => {
                 format!(
-                    "Manual merge required for repository at {}. Resolve conflicts manually.",
+                    "Repository at {} is shallow and {operation} needs history it doesn't have. Fetch more history (e.g. `git fetch --unshallow`) and try again.",
+                    path.display()
+                )
+            }
+            GitError::RepoLocked {
+                path, lock_path, ..
+            } => {
+                format!(
+                    "Repository at {} is busy with another git-ops operation holding {}. Try again later or raise the lock timeout.",
+                    path.display(),
+                    lock_path.display()
+                )
+            }
+            GitError::DirtyWorkingTree { path, .. } => {
+                format!(
+                    "Uncommitted changes at {} would be overwritten. Commit or stash them before pulling.",
+                    path.display()
+                )
+            }
+            GitError::Cancelled { path } => {
+                format!(
+                    "The operation on repository at {} was cancelled before it finished.",
+                    path.display()
+                )
+            }
+            GitError::BranchInUse { branch, path } => {
+                format!(
+                    "Branch '{branch}' is checked out at {}. Checkout another branch before deleting it.",
+                    path.display()
+                )
+            }
+            GitError::BranchNotMerged { branch, path } => {
+                format!(
+                    "Branch '{branch}' at {} has commits that aren't merged. Merge it first or force the delete.",
+                    path.display()
+                )
+            }
+            GitError::RevisionNotFound { rev, path, .. } => {
+                format!(
+                    "Revision '{rev}' was not found at {}. Check the SHA or name, and fetch first if it only exists on the remote.",
                     path.display()
                 )
             }
@@ -100,12 +342,122 @@ impl GitError {
                     path.display()
                 )
             }
+            GitError::UnbornBranch(path) => {
+                format!(
+                    "The current branch of {} has no commits yet and doesn't exist on the remote. Commit first, or checkout a branch the remote has.",
+                    path.display()
+                )
+            }
+            GitError::InvalidBranch(path) => {
+                format!(
+                    "Repository HEAD at {} is not on a branch. Checkout a branch first.",
+                    path.display()
+                )
+            }
+            GitError::ResetFailed { target, path, .. } => {
+                format!(
+                    "Failed to reset repository at {} to {target}. Make sure the target exists (fetch first for remote branches).",
+                    path.display()
+                )
+            }
+            GitError::AutostashPopFailed { path, .. } => {
+                format!(
+                    "Couldn't reapply your local changes after pulling {}. They are kept in the stash; restore them with 'git stash pop'.",
+                    path.display()
+                )
+            }
+            GitError::HardResetNotConfirmed { path } => {
+                format!(
+                    "Refusing to hard reset {} without confirmation, since it discards uncommitted changes.",
+                    path.display()
+                )
+            }
+            GitError::SubmoduleUpdateFailed { name, path, .. } => {
+                format!(
+                    "Failed to update submodule '{name}' of {}. Check the submodule URL and your access to it.",
+                    path.display()
+                )
+            }
+            GitError::InsideSubmodule { path, superproject } => {
+                format!(
+                    "Repository at {} is a submodule of {}. Run the operation on the parent repository or disable submodule rejection.",
+                    path.display(),
+                    superproject.display()
+                )
+            }
+            GitError::NetworkUnreachable { url, .. } => {
+                format!(
+                    "Could not reach remote {url}. Check your network connection, DNS and the remote URL."
+                )
+            }
+            GitError::AuthenticationFailed { url, .. } => {
+                format!(
+                    "Authentication was rejected by remote {url}. Check your SSH keys or access token."
+                )
+            }
+            GitError::SignatureVerificationFailed { target, reason, .. } => {
+                format!(
+                    "Refusing to checkout {target}: {reason}. Make sure it is signed by a trusted key and the signer's key is available to git."
+                )
+            }
+            GitError::CommandFailed {
+                command, stderr, ..
+            } => {
+                format!(
+                    "`{command}` failed: {stderr}. Make sure the git command line tool is installed and on your PATH."
+                )
+            }
+            GitError::InvalidRemoteUrl { url, reason } => {
+                format!(
+                    "Could not parse remote URL {url}: {reason}. Use a URL like https://host/owner/repo.git or git@host:owner/repo.git."
+                )
+            }
+            GitError::Io { path, source } => {
+                format!(
+                    "Failed to access {}: {source}. Check that it exists and you have permission to read it.",
+                    path.display()
+                )
+            }
             GitError::Ssh(ssh_error) => ssh_error.user_message(),
-            _ => self.to_string(),
+            GitError::Git(error) => match error.class() {
+                git2::ErrorClass::Net | git2::ErrorClass::Http => {
+                    format!(
+                        "Network error: {}. Check your network connection and the remote URL.",
+                        error.message()
+                    )
+                }
+                git2::ErrorClass::Ssh => {
+                    format!(
+                        "SSH error: {}. Check your SSH keys and SSH agent.",
+                        error.message()
+                    )
+                }
+                _ if error.code() == git2::ErrorCode::Auth => {
+                    format!(
+                        "Authentication failed: {}. Check your credentials for the remote.",
+                        error.message()
+                    )
+                }
+                _ => format!("Git operation failed: {}.", error.message()),
+            },
         }
     }
 }
 
+/// ` from {url}` when a remote URL is known, for pull messages
+fn from_url(url: &Option<String>) -> String {
+    url.as_ref()
+        .map(|url| format!(" from {url}"))
+        .unwrap_or_default()
+}
+
+/// ` to {url}` when a remote URL is known, for push messages
+fn to_url(url: &Option<String>) -> String {
+    url.as_ref()
+        .map(|url| format!(" to {url}"))
+        .unwrap_or_default()
+}
+
 impl SshError {
     /// Get a user-friendly error message with suggestions
     pub fn user_message(&self) -> String {
@@ -163,3 +515,63 @@ impl SshError {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message_invalid_branch() {
+        let error = GitError::InvalidBranch(PathBuf::from("/test/repo"));
+
+        assert_eq!(
+            error.user_message(),
+            "Repository HEAD at /test/repo is not on a branch. Checkout a branch first."
+        );
+    }
+
+    #[test]
+    fn test_user_message_pull_failed_includes_url() {
+        let error = GitError::PullFailed {
+            path: PathBuf::from("/test/repo"),
+            url: Some("git@github.com:owner/repo.git".to_string()),
+            source: git2::Error::from_str("unexpected EOF"),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Failed to pull repository at /test/repo from git@github.com:owner/repo.git: unexpected EOF"
+        );
+        assert_eq!(
+            error.user_message(),
+            "Failed to pull updates for repository at /test/repo from git@github.com:owner/repo.git. Check your SSH keys and network connection."
+        );
+    }
+
+    #[test]
+    fn test_user_message_network_error() {
+        let error = GitError::Git(git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "failed to connect",
+        ));
+
+        assert_eq!(
+            error.user_message(),
+            "Network error: failed to connect. Check your network connection and the remote URL."
+        );
+    }
+
+    #[test]
+    fn test_user_message_auth_error() {
+        let error = GitError::Git(git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Callback,
+            "rejected",
+        ));
+
+        assert_eq!(
+            error.user_message(),
+            "Authentication failed: rejected. Check your credentials for the remote."
+        );
+    }
+}
@@ -0,0 +1,572 @@
+use git2::{Oid, Remote, Repository};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Ref namespace used to advertise negotiation tips during a fetch
+const HAVE_TIP_REF_PREFIX: &str = "refs/git-ops/have";
+
+/// Distinguishes concurrent fetches within this process, each getting its own tip namespace
+static NEGOTIATION_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Which remote refs a fetch updates
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FetchScope {
+    /// Every branch, per the remote's configured refspecs
+    #[default]
+    AllBranches,
+    /// Only the branch with the same name as the checked-out branch
+    ///
+    /// Falls back to every branch when HEAD is detached or unborn.
+    CurrentBranch,
+    /// Exactly these refspecs, e.g. `+refs/heads/main:refs/remotes/origin/main`
+    Refspecs(Vec<String>),
+}
+
+/// Configuration for how remote refs are fetched into a repository
+#[derive(Debug, Clone, Default)]
+pub struct FetchConfig {
+    /// Namespace that remote-tracking refs are fetched into, if any
+    namespace: Option<String>,
+    /// Commits known to be present locally, advertised to the server as "haves"
+    have_tips: Vec<Oid>,
+    /// Whether to mirror the remote's HEAD symref (default branch) after fetching
+    update_head: bool,
+    /// Glob patterns of remote branches whose tracking refs are removed after fetching
+    ref_exclude: Vec<String>,
+    /// Number of commits of history to fetch from each tip, or `None` for full history
+    depth: Option<u32>,
+    /// Which remote refs are fetched
+    scope: FetchScope,
+}
+
+impl FetchConfig {
+    /// Create a fetch configuration using git's default ref layout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the namespace remote-tracking refs are fetched into
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Fetch remote-tracking refs into `refs/namespaces/<namespace>/refs/remotes/<remote>/*`
+    ///
+    /// This keeps branches from several upstreams mirrored into one repository from
+    /// clobbering each other. Pull and checkout resolve remote branches inside the namespace.
+    pub fn set_namespace(&mut self, namespace: Option<String>) {
+        self.namespace = namespace;
+    }
+
+    /// Get the commits advertised to the server as already present
+    pub fn have_tips(&self) -> &[Oid] {
+        &self.have_tips
+    }
+
+    /// Advertise commits (e.g. the last synced SHA) as already present during fetch negotiation
+    ///
+    /// libgit2 advertises local refs as "haves", so each tip is exposed through a temporary ref
+    /// for the duration of the fetch. This helps detached or shallow repositories whose tips
+    /// aren't otherwise reachable from a ref. Tips that don't exist locally are ignored.
+    pub fn set_have_tips(&mut self, have_tips: Vec<Oid>) {
+        self.have_tips = have_tips;
+    }
+
+    /// Check if the remote's default branch is mirrored after fetching
+    pub fn update_head(&self) -> bool {
+        self.update_head
+    }
+
+    /// Mirror the remote's HEAD symref (its default branch) after fetching
+    ///
+    /// In a bare repository the local `HEAD` is pointed at the default branch so clones of it
+    /// land on the right branch. Otherwise `HEAD` is left alone and the remote-tracking
+    /// `<remote>/HEAD` symref is updated instead, as `git clone` does.
+    pub fn set_update_head(&mut self, update_head: bool) {
+        self.update_head = update_head;
+    }
+
+    /// Get the glob patterns of remote branches that aren't kept after fetching
+    pub fn ref_exclude(&self) -> &[String] {
+        &self.ref_exclude
+    }
+
+    /// Drop remote-tracking refs for branches matching any of these glob patterns after fetching
+    ///
+    /// Patterns match the branch name, e.g. `dependabot/*` or `feature-??`. `*` matches any run
+    /// of characters, including `/`, and `?` matches a single character. Tags aren't affected.
+    pub fn set_ref_exclude(&mut self, ref_exclude: Vec<String>) {
+        self.ref_exclude = ref_exclude;
+    }
+
+    /// Get how many commits of history are fetched, `None` if fetches aren't shallow
+    pub fn depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    /// Fetch and clone only the latest `depth` commits of each branch, like `git fetch --depth`
+    ///
+    /// Pulls into the resulting shallow repository keep working as long as merges don't need
+    /// history past the shallow boundary; operations that do fail with
+    /// `GitError::ShallowBoundary` and need `unshallow` first. The bundled libgit2 (1.7)
+    /// only completes shallow fetches over HTTP(S); SSH and `git://` servers reject them.
+    pub fn set_depth(&mut self, depth: Option<u32>) {
+        self.depth = depth;
+    }
+
+    /// Get which remote refs are fetched
+    pub fn scope(&self) -> &FetchScope {
+        &self.scope
+    }
+
+    /// Limit which remote refs are fetched, e.g. only the current branch in a large monorepo
+    ///
+    /// Remote-tracking refs outside the scope are left as they were. `CurrentBranch` honours
+    /// the namespace; explicit refspecs are used as given.
+    pub fn set_scope(&mut self, scope: FetchScope) {
+        self.scope = scope;
+    }
+
+    /// Apply the configured depth to `fetch_options`
+    pub(crate) fn apply_depth(&self, fetch_options: &mut git2::FetchOptions<'_>) {
+        if let Some(depth) = self.depth {
+            fetch_options.depth(i32::try_from(depth).unwrap_or(i32::MAX));
+        }
+    }
+
+    /// Ref prefix under which the remote's branches are tracked
+    pub(crate) fn tracking_prefix(&self, remote_name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("refs/namespaces/{namespace}/refs/remotes/{remote_name}"),
+            None => format!("refs/remotes/{remote_name}"),
+        }
+    }
+
+    /// Refspecs to fetch with, given the checked-out branch if there is one
+    ///
+    /// An empty list fetches the remote's configured refspecs (all branches).
+    pub(crate) fn refspecs(&self, remote_name: &str, current_branch: Option<&str>) -> Vec<String> {
+        match (&self.scope, current_branch) {
+            (FetchScope::Refspecs(refspecs), _) => return refspecs.clone(),
+            (FetchScope::CurrentBranch, Some(branch)) => {
+                return vec![format!(
+                    "+refs/heads/{branch}:{}/{branch}",
+                    self.tracking_prefix(remote_name)
+                )]
+            }
+            _ => {}
+        }
+
+        match &self.namespace {
+            Some(_) => vec![format!(
+                "+refs/heads/*:{}/*",
+                self.tracking_prefix(remote_name)
+            )],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Delete the remote-tracking refs for branches excluded by `fetch_config`
+pub(crate) fn prune_excluded_refs(
+    repo: &Repository,
+    fetch_config: &FetchConfig,
+    remote_name: &str,
+) -> Result<(), git2::Error> {
+    if fetch_config.ref_exclude.is_empty() {
+        return Ok(());
+    }
+
+    let tracking_prefix = format!("{}/", fetch_config.tracking_prefix(remote_name));
+    let mut excluded = Vec::new();
+    for reference in repo.references_glob(&format!("{tracking_prefix}*"))? {
+        let reference = reference?;
+        let Some(branch_name) = reference
+            .name()
+            .and_then(|name| name.strip_prefix(&tracking_prefix))
+        else {
+            continue;
+        };
+
+        if branch_name != "HEAD"
+            && fetch_config
+                .ref_exclude
+                .iter()
+                .any(|pattern| glob_matches(pattern, branch_name))
+        {
+            excluded.push(reference);
+        }
+    }
+
+    for mut reference in excluded {
+        reference.delete()?;
+    }
+
+    Ok(())
+}
+
+/// Match `text` against a glob `pattern` supporting `*` and `?`
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it is currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                t += 1;
+            }
+            Some(c) if *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Point HEAD (bare repositories) or the remote-tracking HEAD at the remote's default branch
+///
+/// Must be called after `remote` has connected, e.g. right after a fetch.
+pub(crate) fn update_head_symref(
+    repo: &Repository,
+    remote: &Remote,
+    tracking_prefix: &str,
+) -> Result<(), git2::Error> {
+    let default_branch = remote.default_branch()?;
+    let default_branch = default_branch
+        .as_str()
+        .ok_or_else(|| git2::Error::from_str("Remote default branch is not valid UTF-8"))?;
+
+    if repo.is_bare() {
+        repo.set_head(default_branch)?;
+    } else if let Some(branch_name) = default_branch.strip_prefix("refs/heads/") {
+        repo.reference_symbolic(
+            &format!("{tracking_prefix}/HEAD"),
+            &format!("{tracking_prefix}/{branch_name}"),
+            true,
+            "git-ops: update remote HEAD",
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Read the commits listed in the repository's `shallow` file, empty if it isn't shallow
+pub(crate) fn shallow_roots(repo: &Repository) -> Result<Vec<Oid>, git2::Error> {
+    let contents = match std::fs::read_to_string(repo.path().join("shallow")) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(git2::Error::from_str(&e.to_string())),
+    };
+
+    contents
+        .lines()
+        .map(|line| Oid::from_str(line.trim()))
+        .collect()
+}
+
+/// Add back shallow roots that a fetch dropped from the `shallow` file
+///
+/// libgit2 1.7 rewrites the file with only the roots the server sent during the latest
+/// fetch, so commits that were already at the boundary look complete and walking past them
+/// fails. Roots whose parents are still missing are kept; ones the fetch filled in are left
+/// out. Parents are read from the raw commit since the commit parser applies the grafts.
+pub(crate) fn keep_shallow_roots(repo: &Repository, previous: &[Oid]) -> Result<(), git2::Error> {
+    let mut roots = shallow_roots(repo)?;
+    let odb = repo.odb()?;
+    for root in previous {
+        if roots.contains(root) {
+            continue;
+        }
+        let object = odb.read(*root)?;
+        let data = String::from_utf8_lossy(object.data());
+        let parents_missing = data
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.strip_prefix("parent "))
+            .any(|parent| Oid::from_str(parent).map_or(true, |parent| !odb.exists(parent)));
+        if parents_missing {
+            roots.push(*root);
+        }
+    }
+
+    let contents: String = roots.iter().map(|root| format!("{root}\n")).collect();
+    std::fs::write(repo.path().join("shallow"), contents)
+        .map_err(|e| git2::Error::from_str(&e.to_string()))
+}
+
+/// Temporary refs that advertise negotiation tips during a fetch, removed when dropped
+pub(crate) struct NegotiationTips<'repo> {
+    repo: &'repo Repository,
+    ref_names: Vec<String>,
+}
+
+impl<'repo> NegotiationTips<'repo> {
+    /// Create a ref for each tip that exists locally
+    ///
+    /// Each call gets its own `refs/git-ops/have/<pid>-<counter>` namespace so concurrent
+    /// fetches don't clobber each other's tips. Tips left behind by processes that are no
+    /// longer running are removed first.
+    pub(crate) fn create(repo: &'repo Repository, tips: &[Oid]) -> Result<Self, git2::Error> {
+        if tips.is_empty() {
+            return Ok(Self {
+                repo,
+                ref_names: Vec::new(),
+            });
+        }
+
+        Self::remove_stale(repo)?;
+
+        let namespace = format!(
+            "{HAVE_TIP_REF_PREFIX}/{}-{}",
+            std::process::id(),
+            NEGOTIATION_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut negotiation_tips = Self {
+            repo,
+            ref_names: Vec::new(),
+        };
+
+        for (index, tip) in tips.iter().enumerate() {
+            // A commit we don't have can't be advertised as a "have"
+            if repo.find_commit(*tip).is_err() {
+                continue;
+            }
+
+            let ref_name = format!("{namespace}/{index}");
+            repo.reference(&ref_name, *tip, false, "git-ops: negotiation tip")?;
+            negotiation_tips.ref_names.push(ref_name);
+        }
+
+        Ok(negotiation_tips)
+    }
+
+    /// Delete tip refs whose owning process is no longer running, e.g. after a crash
+    fn remove_stale(repo: &Repository) -> Result<(), git2::Error> {
+        let prefix = format!("{HAVE_TIP_REF_PREFIX}/");
+        let mut stale = Vec::new();
+        for reference in repo.references_glob(&format!("{prefix}*"))? {
+            let reference = reference?;
+            let Some(ref_name) = reference.name() else {
+                continue;
+            };
+            let owner = ref_name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.split_once('-'))
+                .and_then(|(pid, _)| pid.parse::<u32>().ok());
+            // Refs from an older layout have no owner and are always stale
+            if owner.is_none_or(|pid| !process_is_running(pid)) {
+                stale.push(reference);
+            }
+        }
+
+        for mut reference in stale {
+            reference.delete()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check if a process with `pid` is running; assumed to be when it can't be determined
+fn process_is_running(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+
+    if cfg!(target_os = "linux") {
+        std::path::Path::new("/proc").join(pid.to_string()).exists()
+    } else if cfg!(unix) {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map_or(true, |status| status.success())
+    } else {
+        true
+    }
+}
+
+impl Drop for NegotiationTips<'_> {
+    fn drop(&mut self) {
+        for ref_name in &self.ref_names {
+            if let Ok(mut reference) = self.repo.find_reference(ref_name) {
+                let _ = reference.delete();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_layout() {
+        let config = FetchConfig::new();
+
+        assert_eq!(config.tracking_prefix("origin"), "refs/remotes/origin");
+        assert!(config.refspecs("origin", Some("main")).is_empty());
+    }
+
+    #[test]
+    fn test_namespaced_layout() {
+        let mut config = FetchConfig::new();
+        config.set_namespace(Some("upstream-a".to_string()));
+
+        assert_eq!(
+            config.tracking_prefix("origin"),
+            "refs/namespaces/upstream-a/refs/remotes/origin"
+        );
+        assert_eq!(
+            config.refspecs("origin", Some("main")),
+            vec!["+refs/heads/*:refs/namespaces/upstream-a/refs/remotes/origin/*"]
+        );
+    }
+
+    #[test]
+    fn test_scoped_refspecs() {
+        let mut config = FetchConfig::new();
+        config.set_scope(FetchScope::CurrentBranch);
+
+        let current = config.refspecs("origin", Some("feature/a"));
+        let detached = config.refspecs("origin", None);
+        config.set_namespace(Some("upstream-a".to_string()));
+        let namespaced = config.refspecs("origin", Some("main"));
+        config.set_scope(FetchScope::Refspecs(vec![
+            "refs/tags/*:refs/tags/*".to_string()
+        ]));
+        let explicit = config.refspecs("origin", Some("main"));
+
+        assert_eq!(
+            current,
+            vec!["+refs/heads/feature/a:refs/remotes/origin/feature/a"]
+        );
+        assert!(detached.is_empty());
+        assert_eq!(
+            namespaced,
+            vec!["+refs/heads/main:refs/namespaces/upstream-a/refs/remotes/origin/main"]
+        );
+        assert_eq!(explicit, vec!["refs/tags/*:refs/tags/*"]);
+    }
+
+    #[test]
+    fn test_negotiation_tips_are_temporary() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        let missing = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+
+        let tips = NegotiationTips::create(&repo, &[missing, commit]).unwrap();
+        let concurrent = NegotiationTips::create(&repo, &[commit]).unwrap();
+        let advertised = have_tip_refs(&repo);
+        assert_eq!(advertised.len(), 2);
+        assert!(advertised.iter().all(|(_, target)| *target == commit));
+        assert_ne!(tips.ref_names, concurrent.ref_names);
+        assert!(tips.ref_names[0].ends_with("/1"));
+
+        drop(tips);
+        assert_eq!(
+            have_tip_refs(&repo)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            concurrent.ref_names
+        );
+        drop(concurrent);
+        assert!(have_tip_refs(&repo).is_empty());
+    }
+
+    #[test]
+    fn test_negotiation_tips_remove_stale_refs() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        // Neither a PID that can't be running nor the old unnamespaced layout has a live owner
+        repo.reference("refs/git-ops/have/4294967295-0/0", commit, false, "test")
+            .unwrap();
+        repo.reference("refs/git-ops/have/0", commit, false, "test")
+            .unwrap();
+
+        let tips = NegotiationTips::create(&repo, &[commit]).unwrap();
+
+        assert_eq!(
+            have_tip_refs(&repo)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            tips.ref_names
+        );
+    }
+
+    fn have_tip_refs(repo: &Repository) -> Vec<(String, Oid)> {
+        repo.references_glob("refs/git-ops/have/*")
+            .unwrap()
+            .map(|reference| {
+                let reference = reference.unwrap();
+                (
+                    reference.name().unwrap().to_string(),
+                    reference.target().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("dependabot/*", "dependabot/npm/lodash"));
+        assert!(glob_matches("release-?", "release-1"));
+        assert!(glob_matches("*-wip", "feature-wip"));
+        assert!(!glob_matches("release-?", "release-10"));
+        assert!(!glob_matches("feature/*", "main"));
+    }
+
+    #[test]
+    fn test_prune_excluded_refs() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        for branch in ["main", "release/1.0", "feature/a", "feature/b"] {
+            repo.reference(
+                &format!("refs/remotes/origin/{branch}"),
+                commit,
+                false,
+                "test",
+            )
+            .unwrap();
+        }
+        let mut config = FetchConfig::new();
+        config.set_ref_exclude(vec!["feature/*".to_string()]);
+
+        prune_excluded_refs(&repo, &config, "origin").unwrap();
+
+        assert!(repo.find_reference("refs/remotes/origin/main").is_ok());
+        assert!(repo
+            .find_reference("refs/remotes/origin/release/1.0")
+            .is_ok());
+        assert!(repo
+            .find_reference("refs/remotes/origin/feature/a")
+            .is_err());
+        assert!(repo
+            .find_reference("refs/remotes/origin/feature/b")
+            .is_err());
+    }
+}
@@ -0,0 +1,345 @@
+use std::path::Path;
+
+use crate::fetch;
+
+/// How a host key compares with the entries of a known_hosts file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HostKeyStatus {
+    /// An entry for the host lists this key
+    Known,
+    /// No entry matches the host
+    Unknown,
+    /// The host is listed, but only with other keys
+    Mismatch,
+    /// The key is listed as `@revoked` for the host
+    Revoked,
+}
+
+/// Name `host` is listed under in known_hosts files: `[host]:port` for ports other than 22
+pub(crate) fn entry_name(host: &str, port: Option<u16>) -> String {
+    match port {
+        Some(port) if port != 22 => format!("[{host}]:{port}"),
+        _ => host.to_string(),
+    }
+}
+
+/// Look up `host` on `port` and its raw SSH public key blob `key` in the known_hosts file at `path`
+///
+/// A missing file knows no hosts. Servers on ports other than 22 only match `[host]:port`
+/// entries, like OpenSSH writes them. Hashed host names (`|1|...`) and `*`, `?` and `!`
+/// patterns are matched like OpenSSH matches them; `@cert-authority` lines are skipped
+/// since host certificates aren't checked.
+pub(crate) fn check(
+    path: &Path,
+    host: &str,
+    port: Option<u16>,
+    key: &[u8],
+) -> std::io::Result<HostKeyStatus> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HostKeyStatus::Unknown),
+        Err(e) => return Err(e),
+    };
+
+    let host = entry_name(&host.to_lowercase(), port);
+    let mut status = HostKeyStatus::Unknown;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut revoked = false;
+        let Some(mut hosts) = fields.next() else {
+            continue;
+        };
+        if let Some(marker) = hosts.strip_prefix('@') {
+            if marker != "revoked" {
+                continue;
+            }
+            revoked = true;
+            let Some(next) = fields.next() else {
+                continue;
+            };
+            hosts = next;
+        }
+        let (Some(_key_type), Some(encoded_key)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if !hosts_match(hosts, &host) {
+            continue;
+        }
+
+        let key_matches = base64_decode(encoded_key).is_some_and(|listed| listed == key);
+        match (revoked, key_matches) {
+            (true, true) => return Ok(HostKeyStatus::Revoked),
+            (true, false) => {}
+            (false, true) => status = HostKeyStatus::Known,
+            (false, false) => {
+                if status == HostKeyStatus::Unknown {
+                    status = HostKeyStatus::Mismatch;
+                }
+            }
+        }
+    }
+
+    Ok(status)
+}
+
+/// Whether the comma separated host `patterns` of a known_hosts line match `host`
+fn hosts_match(patterns: &str, host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns.split(',') {
+        if let Some(hashed) = pattern.strip_prefix("|1|") {
+            matched |= hashed_host_matches(hashed, host);
+        } else if let Some(negated) = pattern.strip_prefix('!') {
+            if fetch::glob_matches(&negated.to_lowercase(), host) {
+                return false;
+            }
+        } else {
+            matched |= fetch::glob_matches(&pattern.to_lowercase(), host);
+        }
+    }
+    matched
+}
+
+/// Whether a hashed host entry (`salt|hash`, both base64) is HMAC-SHA1 of `host` with the salt
+fn hashed_host_matches(hashed: &str, host: &str) -> bool {
+    let Some((salt, hash)) = hashed.split_once('|') else {
+        return false;
+    };
+    match (base64_decode(salt), base64_decode(hash)) {
+        (Some(salt), Some(hash)) => hmac_sha1(&salt, host.as_bytes()).as_slice() == hash,
+        _ => false,
+    }
+}
+
+/// Decode standard, padded base64, `None` if `text` isn't valid
+pub(crate) fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// HMAC-SHA1 of `message` with `key`, as OpenSSH uses for hashed host names
+fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; 20] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..20].copy_from_slice(&sha1(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner: Vec<u8> = block.iter().map(|byte| byte ^ 0x36).collect();
+    inner.extend_from_slice(message);
+    let mut outer: Vec<u8> = block.iter().map(|byte| byte ^ 0x5c).collect();
+    outer.extend_from_slice(&sha1(&inner));
+    sha1(&outer)
+}
+
+/// SHA-1 digest of `data`
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut words = [0u32; 80];
+        for (index, word) in chunk.chunks(4).enumerate() {
+            words[index] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for index in 16..80 {
+            words[index] =
+                (words[index - 3] ^ words[index - 8] ^ words[index - 14] ^ words[index - 16])
+                    .rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (index, word) in words.iter().enumerate() {
+            let (f, k) = match index {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const HOST_KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIASAvd2YYquel6W+7yVRWKVr/ImdbE4sRA3CDfHp8RzA";
+    const OTHER_KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIKO9IUmMkbQfS4fp8F+7AFWGtbfnq8XaJWyKxbU/Aw2A";
+
+    fn check_with(contents: &str, host: &str, key: &str) -> HostKeyStatus {
+        check_with_port(contents, host, None, key)
+    }
+
+    fn check_with_port(contents: &str, host: &str, port: Option<u16>, key: &str) -> HostKeyStatus {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("known_hosts");
+        std::fs::write(&path, contents).unwrap();
+        check(&path, host, port, &base64_decode(key).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_check_plain_entries() {
+        let contents = format!(
+            "# comment\ngit.example.com,10.0.0.1 ssh-ed25519 {HOST_KEY}\n*.internal,!secret.internal ssh-ed25519 {OTHER_KEY}\n"
+        );
+
+        assert_eq!(
+            check_with(&contents, "GIT.example.com", HOST_KEY),
+            HostKeyStatus::Known
+        );
+        assert_eq!(
+            check_with(&contents, "git.example.com", OTHER_KEY),
+            HostKeyStatus::Mismatch
+        );
+        assert_eq!(
+            check_with(&contents, "build.internal", OTHER_KEY),
+            HostKeyStatus::Known
+        );
+        assert_eq!(
+            check_with(&contents, "secret.internal", OTHER_KEY),
+            HostKeyStatus::Unknown
+        );
+        assert_eq!(
+            check_with(&contents, "other.example.com", HOST_KEY),
+            HostKeyStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_check_bracketed_port_entry() {
+        let contents = format!(
+            "[git.example.com]:2222 ssh-ed25519 {HOST_KEY}\nother.example.com ssh-ed25519 {HOST_KEY}\n"
+        );
+
+        assert_eq!(
+            check_with_port(&contents, "git.example.com", Some(2222), HOST_KEY),
+            HostKeyStatus::Known
+        );
+        assert_eq!(
+            check_with_port(&contents, "git.example.com", None, HOST_KEY),
+            HostKeyStatus::Unknown
+        );
+        assert_eq!(
+            check_with_port(&contents, "other.example.com", Some(2222), HOST_KEY),
+            HostKeyStatus::Unknown
+        );
+        assert_eq!(
+            check_with_port(&contents, "other.example.com", Some(22), HOST_KEY),
+            HostKeyStatus::Known
+        );
+    }
+
+    #[test]
+    fn test_check_hashed_entry() {
+        // Written by `ssh-keygen -H` for git.example.com
+        let contents = format!(
+            "|1|4kO1TXieI2GjFGajBg19DNv7tb8=|1891xlSoMbcPz4zPBA8eIl3NvH4= ssh-ed25519 {HOST_KEY}\n"
+        );
+
+        assert_eq!(
+            check_with(&contents, "git.example.com", HOST_KEY),
+            HostKeyStatus::Known
+        );
+        assert_eq!(
+            check_with(&contents, "git.example.org", HOST_KEY),
+            HostKeyStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_check_revoked_key() {
+        let contents =
+            format!("git.example.com ssh-ed25519 {HOST_KEY}\n@revoked * ssh-ed25519 {HOST_KEY}\n");
+
+        assert_eq!(
+            check_with(&contents, "git.example.com", HOST_KEY),
+            HostKeyStatus::Revoked
+        );
+    }
+
+    #[test]
+    fn test_check_missing_file_knows_no_hosts() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let status = check(
+            &temp_dir.path().join("known_hosts"),
+            "git.example.com",
+            None,
+            b"key",
+        );
+
+        assert_eq!(status.unwrap(), HostKeyStatus::Unknown);
+    }
+
+    #[test]
+    fn test_sha1_and_hmac_vectors() {
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+        assert_eq!(
+            hex(&sha1(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hex(&hmac_sha1(
+                b"key",
+                b"The quick brown fox jumps over the lazy dog"
+            )),
+            "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+        );
+    }
+}
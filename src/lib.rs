@@ -1,9 +1,69 @@
+#[macro_use]
+mod logging;
+
 mod auth;
+mod author;
+mod batch;
+mod branch;
+mod builder;
+mod bundle;
 mod checkout;
+mod cherry_pick;
 mod client;
+mod clone;
+mod command;
+mod commit;
+mod diff;
 mod error;
+mod fetch;
+mod known_hosts;
+mod lock;
+mod log;
+mod merge;
+mod notes;
+mod opts;
+mod progress;
 mod pull;
+mod push;
+mod refs;
+mod remote;
+mod repository;
+mod reset;
+mod retry;
+mod revision;
+mod ssh_config;
+mod stash;
+mod status;
+mod submodule;
+mod tag;
+#[cfg(test)]
+mod test_support;
+mod trace;
+mod verify;
 
-pub use auth::SshConfig;
+pub use auth::{SshConfig, TokenAuth, TokenEnvVar};
+pub use author::Author;
+pub use batch::BatchOptions;
+pub use branch::{BranchFilter, BranchInfo};
+pub use builder::GitClientBuilder;
+pub use checkout::{
+    CheckoutChange, CheckoutChangeKind, CheckoutNotification, CheckoutNotifier, CheckoutOptions,
+    CheckoutTarget, ConflictStyle,
+};
 pub use client::GitClient;
+pub use diff::{ChangeKind, FileDiff};
 pub use error::{GitError, SshError};
+pub use fetch::{FetchConfig, FetchScope};
+pub use log::{CommitInfo, LogOptions, LogSort};
+pub use merge::{CommitId, MergeOutcome};
+pub use opts::{network_timeouts, set_network_timeouts};
+pub use progress::Progress;
+pub use pull::{DryRunOutcome, FetchSummary, MergeStrategy, PullOutcome};
+pub use remote::{parse_remote_url, RemoteUrl};
+pub use repository::{OpProgress, RepoInfo};
+pub use reset::ResetMode;
+pub use retry::RetryPolicy;
+pub use revision::DescribeOptions;
+pub use status::{DirtyOptions, FileStatus, StatusEntry};
+pub use submodule::GitmoduleEntry;
+pub use trace::{disable_trace, enable_trace, TraceLevel};
@@ -0,0 +1,102 @@
+use git2::Repository;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::GitError;
+
+/// Name of the advisory lock file inside the git directory
+const LOCK_FILE: &str = "git-ops.lock";
+
+/// How long to wait between attempts to take a held lock
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An advisory lock on a repository, released when dropped
+///
+/// The lock is an exclusive OS file lock (`flock` on Unix, `LockFileEx` on Windows) on a
+/// `git-ops.lock` file in the git directory, so it only excludes other processes that take
+/// the same lock. The OS releases it when the holder exits, so a crashed process can't leave
+/// the repository locked. The file itself is left in place, since deleting it would let a
+/// waiter lock an unlinked file while a newcomer locks a fresh one.
+#[derive(Debug)]
+pub(crate) struct RepoLock {
+    _file: File,
+}
+
+impl RepoLock {
+    /// Take the lock on the repository at `repo_path`, waiting up to `timeout` for another holder to release it
+    ///
+    /// # Errors
+    /// Returns `RepoLocked` if the lock is still held when `timeout` runs out
+    pub(crate) fn acquire(repo_path: &Path, timeout: Duration) -> Result<Self, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let lock_path = repo.path().join(LOCK_FILE);
+        let io_error = |e: std::io::Error| GitError::Io {
+            path: lock_path.clone(),
+            source: e,
+        };
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&lock_path)
+            .map_err(io_error)?;
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(GitError::RepoLocked {
+                            path: repo_path.to_path_buf(),
+                            lock_path,
+                            timeout,
+                        });
+                    }
+                    thread::sleep(RETRY_INTERVAL.min(deadline - now));
+                }
+                Err(TryLockError::Error(e)) => return Err(io_error(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_acquire_times_out_while_held() {
+        let temp_dir = TempDir::new().unwrap();
+        init_repo(temp_dir.path());
+        let held = RepoLock::acquire(temp_dir.path(), Duration::ZERO).unwrap();
+
+        let contended = RepoLock::acquire(temp_dir.path(), Duration::from_millis(100));
+        drop(held);
+        let released = RepoLock::acquire(temp_dir.path(), Duration::ZERO);
+
+        assert!(matches!(contended, Err(GitError::RepoLocked { .. })));
+        assert!(released.is_ok());
+    }
+
+    #[test]
+    fn test_leftover_lock_file_does_not_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        // What a holder that was killed leaves behind
+        std::fs::write(repo.path().join(LOCK_FILE), "").unwrap();
+
+        let lock = RepoLock::acquire(temp_dir.path(), Duration::ZERO);
+
+        assert!(lock.is_ok());
+    }
+}
@@ -0,0 +1,250 @@
+use git2::{Oid, Repository, Sort};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::author::{self, Author};
+use crate::error::GitError;
+
+/// Summary of a single commit in the history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    /// Commit id
+    pub id: Oid,
+    /// First line of the commit message
+    pub summary: String,
+    /// Commit author
+    pub author: Author,
+    /// Commit time
+    pub time: SystemTime,
+}
+
+/// Order in which history is walked; flags combine, e.g. topological and time for changelogs
+///
+/// With no flags set, commits come in libgit2's default order (reverse chronological
+/// as discovered from HEAD).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogSort {
+    /// Sort by commit time, newest first
+    pub time: bool,
+    /// Never show a parent before all of its children
+    pub topological: bool,
+    /// Reverse the resulting order, e.g. oldest first
+    pub reverse: bool,
+}
+
+impl From<LogSort> for Sort {
+    fn from(sort: LogSort) -> Self {
+        let mut flags = Sort::NONE;
+        if sort.time {
+            flags |= Sort::TIME;
+        }
+        if sort.topological {
+            flags |= Sort::TOPOLOGICAL;
+        }
+        if sort.reverse {
+            flags |= Sort::REVERSE;
+        }
+        flags
+    }
+}
+
+/// Options controlling how history is read
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    /// Resolve author identities through the repository's `.mailmap`
+    pub use_mailmap: bool,
+    /// Order commits are returned in
+    pub sort: LogSort,
+    /// Revision to walk back from (branch, tag, SHA, `HEAD~1`, ...), HEAD if `None`
+    pub start_rev: Option<String>,
+    /// Return at most this many commits, all of them if `None`
+    pub limit: Option<usize>,
+}
+
+/// History operations for Git repositories
+pub struct GitLog;
+
+impl GitLog {
+    /// Read the commit history reachable from HEAD or `options.start_rev`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `options` - Options controlling how history is read
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `options.start_rev` doesn't resolve to a commit
+    pub fn log(repo_path: &Path, options: &LogOptions) -> Result<Vec<CommitInfo>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mailmap = if options.use_mailmap {
+            Some(repo.mailmap()?)
+        } else {
+            None
+        };
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(options.sort.into())?;
+        match &options.start_rev {
+            Some(start_rev) => {
+                let start = repo
+                    .revparse_single(start_rev)
+                    .and_then(|object| object.peel_to_commit())
+                    .map_err(|e| GitError::RevisionNotFound {
+                        rev: start_rev.clone(),
+                        path: repo_path.to_path_buf(),
+                        source: e,
+                    })?;
+                revwalk.push(start.id())?;
+            }
+            None => revwalk.push_head()?,
+        }
+
+        let mut commits = Vec::new();
+        for oid in revwalk.take(options.limit.unwrap_or(usize::MAX)) {
+            let commit = repo.find_commit(oid?)?;
+            let author = match &mailmap {
+                Some(mailmap) => Author::from(mailmap.resolve_signature(&commit.author())?),
+                None => Author::from(commit.author()),
+            };
+
+            commits.push(CommitInfo {
+                id: commit.id(),
+                summary: commit.summary().unwrap_or("").to_string(),
+                author,
+                time: author::system_time(commit.time()),
+            });
+        }
+
+        Ok(commits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, commit_file_as, init_repo};
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn mailmapped_repo(temp_dir: &TempDir) -> Repository {
+        let repo = init_repo(temp_dir.path());
+        commit_file(
+            &repo,
+            ".mailmap",
+            "Canonical Name <canonical@example.com> <old@example.com>\n",
+            "Add mailmap",
+        );
+        let old_identity = Signature::now("Old Name", "old@example.com").unwrap();
+        commit_file_as(&repo, &old_identity, "file.txt", "one\n", "Old identity");
+        repo
+    }
+
+    #[test]
+    fn test_log_without_mailmap() {
+        let temp_dir = TempDir::new().unwrap();
+        mailmapped_repo(&temp_dir);
+
+        let commits = GitLog::log(temp_dir.path(), &LogOptions::default()).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].summary, "Old identity");
+        assert_eq!(commits[0].author.name, "Old Name");
+        assert_eq!(commits[0].author.email, "old@example.com");
+    }
+
+    #[test]
+    fn test_log_with_mailmap() {
+        let temp_dir = TempDir::new().unwrap();
+        mailmapped_repo(&temp_dir);
+        let options = LogOptions {
+            use_mailmap: true,
+            ..LogOptions::default()
+        };
+
+        let commits = GitLog::log(temp_dir.path(), &options).unwrap();
+
+        assert_eq!(commits[0].author.name, "Canonical Name");
+        assert_eq!(commits[0].author.email, "canonical@example.com");
+    }
+
+    #[test]
+    fn test_log_reverse_sort() {
+        let temp_dir = TempDir::new().unwrap();
+        mailmapped_repo(&temp_dir);
+        let options = LogOptions {
+            sort: LogSort {
+                topological: true,
+                reverse: true,
+                ..LogSort::default()
+            },
+            ..LogOptions::default()
+        };
+
+        let commits = GitLog::log(temp_dir.path(), &options).unwrap();
+
+        let summaries: Vec<&str> = commits
+            .iter()
+            .map(|commit| commit.summary.as_str())
+            .collect();
+        assert_eq!(summaries, vec!["Add mailmap", "Old identity"]);
+    }
+
+    #[test]
+    fn test_log_from_start_rev_with_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        for n in 1..=4 {
+            commit_file(&repo, "file.txt", &format!("{n}\n"), &format!("Commit {n}"));
+        }
+        let summaries = |options: &LogOptions| -> Vec<String> {
+            GitLog::log(temp_dir.path(), options)
+                .unwrap()
+                .into_iter()
+                .map(|commit| commit.summary)
+                .collect()
+        };
+
+        let latest = summaries(&LogOptions {
+            limit: Some(2),
+            ..LogOptions::default()
+        });
+        let earlier = summaries(&LogOptions {
+            start_rev: Some("HEAD~1".to_string()),
+            limit: Some(10),
+            ..LogOptions::default()
+        });
+
+        assert_eq!(latest, vec!["Commit 4", "Commit 3"]);
+        assert_eq!(earlier, vec!["Commit 3", "Commit 2", "Commit 1"]);
+    }
+
+    #[test]
+    fn test_log_unknown_start_rev() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "First");
+        let options = LogOptions {
+            start_rev: Some("missing".to_string()),
+            ..LogOptions::default()
+        };
+
+        let result = GitLog::log(temp_dir.path(), &options);
+
+        assert!(matches!(result, Err(GitError::RevisionNotFound { rev, .. }) if rev == "missing"));
+    }
+
+    #[test]
+    fn test_log_sort_flags() {
+        let sort = LogSort {
+            time: true,
+            topological: true,
+            reverse: false,
+        };
+
+        assert_eq!(Sort::from(sort), Sort::TIME | Sort::TOPOLOGICAL);
+        assert_eq!(Sort::from(LogSort::default()), Sort::NONE);
+    }
+}
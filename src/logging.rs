@@ -0,0 +1,96 @@
+// Logging through the `log` crate when the `log` feature is enabled.
+//
+// Without the feature the debug and trace macros still type-check their arguments but
+// compile to nothing, and warnings go to stderr, so call sites don't need their own `cfg`
+// attributes.
+
+#[cfg(feature = "log")]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        ::log::warn!(target: "git_ops", $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        ::std::eprintln!("Warning: {}", ::std::format_args!($($arg)+))
+    };
+}
+
+#[cfg(feature = "log")]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        ::log::debug!(target: "git_ops", $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = ::std::format_args!($($arg)+);
+        }
+    };
+}
+
+#[cfg(feature = "log")]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        ::log::trace!(target: "git_ops", $($arg)+)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = ::std::format_args!($($arg)+);
+        }
+    };
+}
+
+/// A logger recording every message, for asserting on what was logged
+#[cfg(all(test, feature = "log"))]
+pub(crate) mod capture {
+    use std::sync::{Mutex, Once};
+
+    static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static INSTALL: Once = Once::new();
+
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            MESSAGES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Install the capturing logger; the logger is process-wide, so tests share it
+    pub(crate) fn install() {
+        INSTALL.call_once(|| {
+            log::set_logger(&CaptureLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+    }
+
+    /// Messages logged so far that contain `needle`, formatted as `LEVEL message`
+    pub(crate) fn messages_containing(needle: &str) -> Vec<String> {
+        MESSAGES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|message| message.contains(needle))
+            .cloned()
+            .collect()
+    }
+}
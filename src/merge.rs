@@ -0,0 +1,228 @@
+use git2::{AnnotatedCommit, ErrorCode, Oid, Repository};
+use std::path::Path;
+
+use crate::author::{self, Author};
+use crate::error::GitError;
+use crate::repository;
+
+/// A commit resolved from a reference, to be merged with `GitClient::merge`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitId {
+    /// Commit the reference pointed to when it was resolved
+    pub id: Oid,
+    /// Reference the commit was resolved from, e.g. `FETCH_HEAD` or `refs/tags/v1.0.0`
+    pub refname: String,
+}
+
+/// What merging into HEAD did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// HEAD already contained the merged commit
+    UpToDate,
+    /// HEAD was fast-forwarded from `from` to `to`
+    FastForwarded { from: Oid, to: Oid },
+    /// HEAD and the merged commit had diverged and were joined by merge commit `commit`
+    Merged { commit: Oid },
+}
+
+/// Merge operations for Git repositories
+pub struct GitMerge;
+
+impl GitMerge {
+    /// Resolve a reference to the commit it points to, for merging with `merge`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `refname` - Reference or revision to resolve, e.g. `FETCH_HEAD`, `origin/main` or `v1.0.0`
+    pub fn annotated_commit_from_ref(
+        repo_path: &Path,
+        refname: &str,
+    ) -> Result<CommitId, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let annotated = match repo.resolve_reference_from_short_name(refname) {
+            Ok(reference) => repo.reference_to_annotated_commit(&reference)?,
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                let commit = repo.revparse_single(refname)?.peel_to_commit()?;
+                repo.find_annotated_commit(commit.id())?
+            }
+            Err(e) => return Err(GitError::Git(e)),
+        };
+
+        Ok(CommitId {
+            id: annotated.id(),
+            refname: refname.to_string(),
+        })
+    }
+
+    /// Merge `commit` into HEAD, fast-forwarding when possible and creating a merge commit otherwise
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commit` - Commit to merge, from `annotated_commit_from_ref`
+    ///
+    /// # Errors
+    /// Returns `MergeRequired` without changing anything if the merge conflicts
+    pub fn merge(repo_path: &Path, commit: &CommitId) -> Result<MergeOutcome, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let theirs = repo.find_annotated_commit(commit.id)?;
+        merge_into_head(
+            &repo,
+            repo_path,
+            "merge",
+            &theirs,
+            &commit.refname,
+            &format!("Merge {}", commit.refname),
+            None,
+            GitError::Git,
+        )
+    }
+}
+
+/// Merge `theirs` into HEAD, fast-forwarding when possible and otherwise committing `message`
+///
+/// The working tree is updated with a safe checkout. A conflicting merge returns
+/// `MergeRequired` before anything is written. `name` describes `theirs` in the reflog,
+/// and git errors are mapped through `error`. Merge commits are signed as `signature`, see
+/// `author::commit_signature`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn merge_into_head(
+    repo: &Repository,
+    repo_path: &Path,
+    operation: &str,
+    theirs: &AnnotatedCommit,
+    name: &str,
+    message: &str,
+    signature: Option<&Author>,
+    error: impl Fn(git2::Error) -> GitError + Copy,
+) -> Result<MergeOutcome, GitError> {
+    let shallow_error =
+        |e: git2::Error| repository::shallow_boundary_error(repo, repo_path, operation, e, error);
+
+    let head = repo.head().map_err(error)?;
+    let head_oid = head
+        .target()
+        .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+
+    let (analysis, _) = repo.merge_analysis(&[theirs]).map_err(shallow_error)?;
+    if analysis.is_up_to_date() {
+        return Ok(MergeOutcome::UpToDate);
+    }
+
+    if analysis.is_fast_forward() {
+        let target = repo.find_object(theirs.id(), None).map_err(error)?;
+        repo.checkout_tree(
+            &target,
+            Some(git2::build::CheckoutBuilder::default().safe()),
+        )
+        .map_err(error)?;
+        repo.reference(
+            head.name().unwrap_or("HEAD"),
+            theirs.id(),
+            true,
+            &format!("merge {name}: Fast-forward"),
+        )
+        .map_err(error)?;
+
+        return Ok(MergeOutcome::FastForwarded {
+            from: head_oid,
+            to: theirs.id(),
+        });
+    }
+
+    let ours = repo.find_commit(head_oid).map_err(error)?;
+    let their_commit = repo.find_commit(theirs.id()).map_err(error)?;
+    let mut index = repo
+        .merge_commits(&ours, &their_commit, None)
+        .map_err(shallow_error)?;
+    if index.has_conflicts() {
+        return Err(GitError::MergeRequired {
+            path: repo_path.to_path_buf(),
+            local: head_oid,
+            remote: theirs.id(),
+        });
+    }
+
+    let tree_id = index.write_tree_to(repo).map_err(error)?;
+    let tree = repo.find_tree(tree_id).map_err(error)?;
+    repo.checkout_tree(
+        tree.as_object(),
+        Some(git2::build::CheckoutBuilder::default().safe()),
+    )
+    .map_err(error)?;
+
+    let signature = author::commit_signature(repo, signature).map_err(error)?;
+    let commit = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &[&ours, &their_commit],
+        )
+        .map_err(error)?;
+
+    Ok(MergeOutcome::Merged { commit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_tag_into_diverged_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let base = commit_file(&repo, "base.txt", "base\n", "Base");
+        let base = repo.find_commit(base).unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        let feature = commit_file(&repo, "feature.txt", "feature\n", "Feature");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(feature, None).unwrap(), false)
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+            .unwrap();
+        let main = commit_file(&repo, "main.txt", "main\n", "Main");
+
+        let commit = GitMerge::annotated_commit_from_ref(temp_dir.path(), "v1.0.0").unwrap();
+        let outcome = GitMerge::merge(temp_dir.path(), &commit).unwrap();
+
+        assert_eq!(commit.id, feature);
+        let MergeOutcome::Merged { commit: merge } = outcome else {
+            panic!("expected a merge commit, got {outcome:?}");
+        };
+        let merge = repo.find_commit(merge).unwrap();
+        assert_eq!(merge.parent_ids().collect::<Vec<_>>(), vec![main, feature]);
+        assert_eq!(merge.message(), Some("Merge v1.0.0"));
+        assert!(temp_dir.path().join("feature.txt").exists());
+    }
+
+    #[test]
+    fn test_annotated_commit_from_fetch_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        std::fs::write(
+            repo.path().join("FETCH_HEAD"),
+            format!("{commit}\t\tbranch 'main' of https://example.com/repo\n"),
+        )
+        .unwrap();
+
+        let resolved = GitMerge::annotated_commit_from_ref(temp_dir.path(), "FETCH_HEAD").unwrap();
+        let outcome = GitMerge::merge(temp_dir.path(), &resolved).unwrap();
+
+        assert_eq!(resolved.id, commit);
+        assert_eq!(outcome, MergeOutcome::UpToDate);
+    }
+}
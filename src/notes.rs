@@ -0,0 +1,114 @@
+use git2::{ErrorCode, Oid, Repository};
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// Git notes operations, for attaching metadata to commits without rewriting them
+pub struct GitNotes;
+
+impl GitNotes {
+    /// Attach a note to `commit`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commit` - Commit to annotate
+    /// * `message` - Contents of the note
+    /// * `notes_ref` - Notes ref to store the note under, e.g. `refs/notes/deploys`; `None` uses
+    ///   `core.notesRef`, falling back to `refs/notes/commits`
+    /// * `force` - Replace an existing note instead of failing
+    ///
+    /// # Returns
+    /// The id of the note blob
+    pub fn add_note(
+        repo_path: &Path,
+        commit: Oid,
+        message: &str,
+        notes_ref: Option<&str>,
+        force: bool,
+    ) -> Result<Oid, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let signature = repo.signature()?;
+        Ok(repo.note(&signature, &signature, notes_ref, commit, message, force)?)
+    }
+
+    /// Read the note attached to `commit`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `commit` - Commit to read the note of
+    /// * `notes_ref` - Notes ref to read from; `None` uses `core.notesRef`, falling back to
+    ///   `refs/notes/commits`
+    ///
+    /// # Returns
+    /// `None` if the commit has no note under `notes_ref`
+    pub fn read_note(
+        repo_path: &Path,
+        commit: Oid,
+        notes_ref: Option<&str>,
+    ) -> Result<Option<String>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let note = match repo.find_note(notes_ref, commit) {
+            Ok(note) => Some(String::from_utf8_lossy(note.message_bytes()).into_owned()),
+            Err(e) if e.code() == ErrorCode::NotFound => None,
+            Err(e) => return Err(GitError::Git(e)),
+        };
+
+        Ok(note)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_add_and_read_note_custom_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        GitNotes::add_note(
+            temp_dir.path(),
+            commit,
+            "build 42",
+            Some("refs/notes/deploys"),
+            false,
+        )
+        .unwrap();
+        let deploy_note =
+            GitNotes::read_note(temp_dir.path(), commit, Some("refs/notes/deploys")).unwrap();
+        let default_note = GitNotes::read_note(temp_dir.path(), commit, None).unwrap();
+
+        assert_eq!(deploy_note.as_deref(), Some("build 42"));
+        assert_eq!(default_note, None);
+    }
+
+    #[test]
+    fn test_add_note_force_replaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        GitNotes::add_note(temp_dir.path(), commit, "first", None, false).unwrap();
+
+        let duplicate = GitNotes::add_note(temp_dir.path(), commit, "second", None, false);
+        GitNotes::add_note(temp_dir.path(), commit, "second", None, true).unwrap();
+
+        assert!(duplicate.is_err());
+        assert_eq!(
+            GitNotes::read_note(temp_dir.path(), commit, None)
+                .unwrap()
+                .as_deref(),
+            Some("second")
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::os::raw::c_int;
+use std::time::Duration;
+
+use crate::error::GitError;
+
+// libgit2 1.7 option ids that libgit2-sys doesn't have constants for yet
+const GIT_OPT_SET_SERVER_CONNECT_TIMEOUT: c_int = 39;
+const GIT_OPT_GET_SERVER_CONNECT_TIMEOUT: c_int = 40;
+const GIT_OPT_SET_SERVER_TIMEOUT: c_int = 41;
+const GIT_OPT_GET_SERVER_TIMEOUT: c_int = 42;
+
+/// Set the process-wide timeouts libgit2 uses for server connections
+///
+/// Every fetch, clone and push inherits these. They apply to libgit2's own sockets (SSH,
+/// `git://` and its built-in HTTP transport) and cover the TCP connect and idle phases that
+/// a transfer timeout can't. A zero duration means no timeout, libgit2's default.
+///
+/// # Arguments
+/// * `connect` - How long to wait for a connection to be established
+/// * `idle` - How long to wait for the server to send or accept data
+pub fn set_network_timeouts(connect: Duration, idle: Duration) -> Result<(), GitError> {
+    set_timeout(GIT_OPT_SET_SERVER_CONNECT_TIMEOUT, connect)?;
+    set_timeout(GIT_OPT_SET_SERVER_TIMEOUT, idle)
+}
+
+/// Get the connect and idle timeouts set with `set_network_timeouts`
+pub fn network_timeouts() -> Result<(Duration, Duration), GitError> {
+    Ok((
+        get_timeout(GIT_OPT_GET_SERVER_CONNECT_TIMEOUT)?,
+        get_timeout(GIT_OPT_GET_SERVER_TIMEOUT)?,
+    ))
+}
+
+fn set_timeout(option: c_int, timeout: Duration) -> Result<(), GitError> {
+    let millis = c_int::try_from(timeout.as_millis()).unwrap_or(c_int::MAX);
+
+    libgit2_sys::init();
+    // SAFETY: both timeout options take a single `int` argument
+    let code = unsafe { libgit2_sys::git_libgit2_opts(option, millis) };
+    check(code)
+}
+
+fn get_timeout(option: c_int) -> Result<Duration, GitError> {
+    let mut millis: c_int = 0;
+
+    libgit2_sys::init();
+    // SAFETY: both timeout options write the timeout through a single `int *` argument
+    let code = unsafe { libgit2_sys::git_libgit2_opts(option, &mut millis as *mut c_int) };
+    check(code)?;
+
+    Ok(Duration::from_millis(millis.max(0) as u64))
+}
+
+fn check(code: c_int) -> Result<(), GitError> {
+    if code < 0 {
+        let error = git2::Error::last_error(code)
+            .unwrap_or_else(|| git2::Error::from_str("Failed to set libgit2 option"));
+        return Err(GitError::Git(error));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_network_timeouts() {
+        let connect = Duration::from_secs(5);
+        let idle = Duration::from_millis(30_500);
+
+        set_network_timeouts(connect, idle).unwrap();
+        let timeouts = network_timeouts().unwrap();
+        set_network_timeouts(Duration::ZERO, Duration::ZERO).unwrap();
+
+        assert_eq!(timeouts, (connect, idle));
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Snapshot of a fetch or clone transfer passed to progress callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Objects received so far
+    pub received_objects: usize,
+    /// Objects the server said it would send
+    pub total_objects: usize,
+    /// Objects indexed into the local pack so far
+    pub indexed_objects: usize,
+    /// Bytes received so far
+    pub received_bytes: usize,
+}
+
+impl From<git2::Progress<'_>> for Progress {
+    fn from(stats: git2::Progress<'_>) -> Self {
+        Self {
+            received_objects: stats.received_objects(),
+            total_objects: stats.total_objects(),
+            indexed_objects: stats.indexed_objects(),
+            received_bytes: stats.received_bytes(),
+        }
+    }
+}
+
+/// Callback receiving transfer progress, shared between clones of a puller or cloner
+pub(crate) type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// Report transfer progress from `callbacks` to `progress`, and abort once `cancel` is set
+///
+/// With an `interval`, updates closer together than it are dropped, except the one that
+/// completes the transfer. The cancel flag is checked on every update, throttled or not.
+pub(crate) fn report_transfer(
+    callbacks: &mut git2::RemoteCallbacks<'_>,
+    progress: Option<&ProgressCallback>,
+    interval: Option<Duration>,
+    cancel: Option<&Arc<AtomicBool>>,
+) {
+    if progress.is_none() && cancel.is_none() {
+        return;
+    }
+
+    let progress = progress.cloned();
+    let cancel = cancel.cloned();
+    let mut throttle = Throttle::new(interval);
+    callbacks.transfer_progress(move |stats| {
+        let stats = Progress::from(stats);
+        if let Some(progress) = &progress {
+            if throttle.should_emit(&stats, Instant::now()) {
+                progress(stats);
+            }
+        }
+        !is_cancelled(cancel.as_ref())
+    });
+}
+
+/// Whether the optional cancel flag has been set
+pub(crate) fn is_cancelled(cancel: Option<&Arc<AtomicBool>>) -> bool {
+    cancel.is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+}
+
+/// Decides which progress updates are passed on when updates are rate limited
+struct Throttle {
+    interval: Option<Duration>,
+    last_emitted: Option<Instant>,
+    completed: bool,
+}
+
+impl Throttle {
+    fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval,
+            last_emitted: None,
+            completed: false,
+        }
+    }
+
+    /// Whether `stats`, received at `now`, should reach the callback
+    fn should_emit(&mut self, stats: &Progress, now: Instant) -> bool {
+        let Some(interval) = self.interval else {
+            return true;
+        };
+
+        let complete = stats.total_objects > 0
+            && stats.received_objects == stats.total_objects
+            && stats.indexed_objects == stats.total_objects;
+        let emit = if complete {
+            !std::mem::replace(&mut self.completed, true)
+        } else {
+            self.last_emitted
+                .is_none_or(|last_emitted| now.duration_since(last_emitted) >= interval)
+        };
+
+        if emit {
+            self.last_emitted = Some(now);
+        }
+        emit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(received_objects: usize, indexed_objects: usize) -> Progress {
+        Progress {
+            received_objects,
+            total_objects: 10,
+            indexed_objects,
+            received_bytes: received_objects * 100,
+        }
+    }
+
+    #[test]
+    fn test_throttle_limits_updates_and_keeps_completion() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(Some(Duration::from_millis(100)));
+
+        let emitted: Vec<bool> = [
+            (progress(1, 0), 0),
+            (progress(2, 0), 10),
+            (progress(5, 0), 99),
+            (progress(8, 0), 100),
+            (progress(10, 10), 120),
+            (progress(10, 10), 300),
+        ]
+        .iter()
+        .map(|(stats, millis)| throttle.should_emit(stats, start + Duration::from_millis(*millis)))
+        .collect();
+
+        assert_eq!(emitted, vec![true, false, false, true, true, false]);
+    }
+
+    #[test]
+    fn test_no_interval_emits_every_update() {
+        let now = Instant::now();
+        let mut throttle = Throttle::new(None);
+
+        assert!(throttle.should_emit(&progress(1, 0), now));
+        assert!(throttle.should_emit(&progress(1, 0), now));
+    }
+}
@@ -1,223 +1,2660 @@
-use git2::Repository;
+use git2::{Direction, Oid, Repository};
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::auth::{CredentialCallback, SshConfig};
+use crate::auth::{self, SshConfig, TokenAuth};
+use crate::author::{self, Author};
+use crate::checkout::CheckoutNotifier;
+use crate::command;
 use crate::error::GitError;
+use crate::fetch::{self, FetchConfig, NegotiationTips};
+use crate::merge::{self, MergeOutcome};
+use crate::progress::{self, Progress, ProgressCallback};
+use crate::remote;
+use crate::repository;
+use crate::retry::RetryPolicy;
+use crate::stash::GitStash;
+
+/// What a pull did to the local branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullOutcome {
+    /// The local branch already matched the remote
+    UpToDate,
+    /// The local branch was fast-forwarded from commit `old` to the remote's `new`
+    ///
+    /// `old` is `Oid::zero()` when the branch was unborn and has been created at `new`.
+    FastForwarded { old: Oid, new: Oid },
+    /// The local branch had diverged and was joined with the remote by merge commit `commit`
+    Merged { commit: Oid },
+    /// The local branch had diverged and its commits were replayed onto the remote, ending at `head`
+    Rebased { head: Oid },
+}
+
+/// What a pull would do to the local branch, as reported by `pull_dry_run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DryRunOutcome {
+    /// The local branch already matches the remote
+    UpToDate,
+    /// The local branch would be fast-forwarded from commit `old` to the remote's `new`
+    ///
+    /// `old` is `Oid::zero()` when the branch is unborn and would be created at `new`.
+    FastForward { old: Oid, new: Oid },
+    /// The local branch has diverged and would be joined with the remote's `remote` by a merge commit
+    Merge { local: Oid, remote: Oid },
+    /// The local branch has diverged and its commits would be replayed onto the remote's `remote`
+    Rebase { local: Oid, remote: Oid },
+}
+
+/// How a pull reconciles a local branch that has diverged from the remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Fail with `MergeRequired`, like `git pull --ff-only`
+    #[default]
+    FastForwardOnly,
+    /// Create a merge commit, like `git pull --no-rebase`
+    Merge,
+    /// Replay local commits onto the remote branch, like `git pull --rebase`
+    Rebase,
+}
+
+/// Transfer statistics from a fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchSummary {
+    /// Objects the server said it would send
+    pub total_objects: usize,
+    /// Objects received over the network
+    pub received_objects: usize,
+    /// Objects reused from the local object database instead of being received
+    pub local_objects: usize,
+    /// Bytes received over the network
+    pub received_bytes: usize,
+}
+
+impl From<git2::Progress<'_>> for FetchSummary {
+    fn from(stats: git2::Progress<'_>) -> Self {
+        Self {
+            total_objects: stats.total_objects(),
+            received_objects: stats.received_objects(),
+            local_objects: stats.local_objects(),
+            received_bytes: stats.received_bytes(),
+        }
+    }
+}
 
 /// Pull operations for Git repositories
+#[derive(Clone)]
 pub struct GitPuller {
     ssh_config: SshConfig,
+    fetch_config: FetchConfig,
+    /// Token offered to HTTPS remotes before the credential helper and environment
+    https_token: Option<String>,
+    /// Environment variables tried for HTTPS tokens after the credential helper
+    token_auth: TokenAuth,
+    /// Remote that pull, fetch and reset operate on
+    remote_name: String,
+    /// Proxy for HTTP(S) remotes, `None` to detect it from git config and the environment
+    proxy: Option<String>,
+    /// Called with transfer progress while fetching
+    progress: Option<ProgressCallback>,
+    /// Minimum time between progress updates, `None` to report every update
+    progress_interval: Option<Duration>,
+    /// Aborts the fetch once set, see `pull_with_cancel`
+    cancel: Option<Arc<AtomicBool>>,
+    /// Retries for fetches that fail with transient network errors
+    retry_policy: RetryPolicy,
+    /// Discard local modifications in the way of a fast-forward instead of failing
+    force_checkout: bool,
+    /// Update submodules recursively after each pull
+    update_submodules: bool,
+    /// How `pull` and `pull_if_behind` reconcile a diverged local branch
+    merge_strategy: MergeStrategy,
+    /// Stash local changes around pulls that may merge or rebase
+    autostash: bool,
+    /// Identity merge and rebase commits are signed with, `None` to use git config
+    signature: Option<Author>,
+    /// Told about the files a fast-forward checkout updates or finds in its way
+    checkout_notifier: Option<CheckoutNotifier>,
 }
 
 impl GitPuller {
     /// Create a new GitPuller with the provided SSH configuration
     pub fn new(ssh_config: SshConfig) -> Self {
-        Self { ssh_config }
+        Self {
+            ssh_config,
+            fetch_config: FetchConfig::default(),
+            https_token: None,
+            token_auth: TokenAuth::default(),
+            proxy: None,
+            remote_name: "origin".to_string(),
+            progress: None,
+            progress_interval: None,
+            cancel: None,
+            retry_policy: RetryPolicy::default(),
+            force_checkout: false,
+            update_submodules: false,
+            merge_strategy: MergeStrategy::default(),
+            autostash: false,
+            signature: None,
+            checkout_notifier: None,
+        }
+    }
+
+    /// Get the remote that pull, fetch and reset operate on
+    pub fn remote_name(&self) -> &str {
+        &self.remote_name
+    }
+
+    /// Set the remote that pull, fetch and reset operate on; defaults to `origin`
+    pub fn set_remote_name(&mut self, remote_name: String) {
+        self.remote_name = remote_name;
+    }
+
+    /// Get how `pull` and `pull_if_behind` reconcile a diverged local branch
+    pub fn merge_strategy(&self) -> MergeStrategy {
+        self.merge_strategy
+    }
+
+    /// Set how `pull` and `pull_if_behind` reconcile a diverged local branch; defaults to fast-forward only
+    pub fn set_merge_strategy(&mut self, merge_strategy: MergeStrategy) {
+        self.merge_strategy = merge_strategy;
+    }
+
+    /// Get the fetch configuration
+    pub fn fetch_config(&self) -> &FetchConfig {
+        &self.fetch_config
+    }
+
+    /// Set the fetch configuration
+    pub fn set_fetch_config(&mut self, fetch_config: FetchConfig) {
+        self.fetch_config = fetch_config;
+    }
+
+    /// Offer `https_token` to HTTPS remotes before the credential helper and environment tokens
+    pub fn set_https_token(&mut self, https_token: Option<String>) {
+        self.https_token = https_token;
+    }
+
+    /// Get the environment variables tried for HTTPS tokens
+    pub fn token_auth(&self) -> &TokenAuth {
+        &self.token_auth
+    }
+
+    /// Set the environment variables tried for HTTPS tokens; defaults to GitHub's
+    pub fn set_token_auth(&mut self, token_auth: TokenAuth) {
+        self.token_auth = token_auth;
+    }
+
+    /// Get the proxy for HTTP(S) remotes, `None` if it's detected from git config and the environment
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// Connect to HTTP(S) remotes through `proxy`; `None` detects it from git config and the environment
+    pub fn set_proxy(&mut self, proxy: Option<String>) {
+        self.proxy = proxy;
+    }
+
+    /// Call `callback` with transfer progress while fetching, replacing any earlier callback
+    pub fn set_progress_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+    }
+
+    /// Stop reporting transfer progress
+    pub fn clear_progress_callback(&mut self) {
+        self.progress = None;
+    }
+
+    /// Report progress at most once per `interval`, plus once when the transfer completes
+    ///
+    /// `None` reports every update libgit2 sends, which can be thousands per second.
+    pub fn set_progress_interval(&mut self, interval: Option<Duration>) {
+        self.progress_interval = interval;
+    }
+
+    /// Get the retries for fetches that fail with transient network errors
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Retry fetches that fail with transient network errors under `retry_policy`
+    ///
+    /// Defaults to a single attempt. Only the fetch is retried; a failed merge or checkout
+    /// after it isn't.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Let a fast-forward pull overwrite local modifications to the files it updates
+    ///
+    /// Off by default, so a pull that would discard uncommitted changes fails with
+    /// `DirtyWorkingTree` and leaves the branch where it was.
+    pub fn set_force_checkout(&mut self, force_checkout: bool) {
+        self.force_checkout = force_checkout;
+    }
+
+    /// Report the files a pull's checkout updates or finds in its way to `notifier`
+    ///
+    /// Covers fast-forwards and the first pull into an unborn branch. Pass `None` to stop
+    /// reporting them.
+    pub fn set_checkout_notifier(&mut self, notifier: Option<CheckoutNotifier>) {
+        self.checkout_notifier = notifier;
+    }
+
+    /// Update submodules recursively after each pull, so they match the pulled commit
+    pub fn set_update_submodules(&mut self, update_submodules: bool) {
+        self.update_submodules = update_submodules;
+    }
+
+    /// Stash uncommitted changes before a pull that may merge or rebase, and pop them after
+    ///
+    /// Applies to `MergeStrategy::Merge` and `MergeStrategy::Rebase`; fast-forward-only pulls
+    /// already keep local changes that don't conflict. Off by default.
+    pub fn set_autostash(&mut self, autostash: bool) {
+        self.autostash = autostash;
+    }
+
+    /// Sign merge commits and rebased commits as `signature` instead of git config's identity
+    ///
+    /// With `None`, the default, `user.name` and `user.email` are used, falling back to
+    /// `git-ops <git-ops@localhost>` when git config has no identity.
+    pub fn set_signature(&mut self, signature: Option<Author>) {
+        self.signature = signature;
     }
 
     /// Pull updates for an existing repository
     ///
+    /// A bare repository, such as a mirror clone, is only fetched, updating its refs.
+    ///
     /// # Arguments
     /// * `repo_path` - Path to the repository to update
     pub fn pull(&self, repo_path: &Path) -> Result<(), GitError> {
+        self.pull_detailed(repo_path).map(|_| ())
+    }
+
+    /// Pull updates like `pull`, reporting whether the local branch moved
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    pub fn pull_detailed(&self, repo_path: &Path) -> Result<PullOutcome, GitError> {
+        self.pull_with_strategy(repo_path, self.merge_strategy)
+    }
+
+    /// Pull updates like `pull`, aborting the fetch once `cancel` is set
+    ///
+    /// The flag is checked on each transfer progress update, so a fetch stops shortly after
+    /// another thread sets it, without touching the local branch or working tree. The
+    /// objects received so far are discarded.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    /// * `cancel` - Flag that aborts the pull with `Cancelled` when set
+    pub fn pull_with_cancel(
+        &self,
+        repo_path: &Path,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<(), GitError> {
+        let mut puller = self.clone();
+        puller.cancel = Some(cancel);
+        puller.pull(repo_path)
+    }
+
+    /// Pull updates, reconciling a diverged local branch with `strategy`
+    ///
+    /// A conflicting merge or rebase is abandoned before HEAD moves and returns
+    /// `MergeRequired`, with the fetched commit left on the remote-tracking branch.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    /// * `strategy` - What to do when the local branch has diverged from the remote
+    pub fn pull_with_strategy(
+        &self,
+        repo_path: &Path,
+        strategy: MergeStrategy,
+    ) -> Result<PullOutcome, GitError> {
+        self.pull_branch(repo_path, false, strategy)
+    }
+
+    /// Fetch and report what `pull_detailed` would do, without moving the local branch or touching the working tree
+    ///
+    /// The remote-tracking refs are updated by the fetch, like `fetch`. A diverged branch under
+    /// `MergeStrategy::FastForwardOnly` returns `MergeRequired` as a pull would.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn pull_dry_run(&self, repo_path: &Path) -> Result<DryRunOutcome, GitError> {
         let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
             path: repo_path.to_path_buf(),
             source: e,
         })?;
-
-        // Get the current branch
-        let head = repo.head().map_err(|e| GitError::PullFailed {
+        let pull_error = |e: git2::Error| GitError::PullFailed {
             path: repo_path.to_path_buf(),
+            url: None,
             source: e,
-        })?;
+        };
 
+        let head = repo.find_reference("HEAD").map_err(pull_error)?;
         let branch_name = head
-            .shorthand()
-            .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?
+            .to_string();
+        let local = repo
+            .refname_to_id(&format!("refs/heads/{branch_name}"))
+            .ok();
 
-        // Find the remote (assume origin)
-        let mut remote = repo
-            .find_remote("origin")
-            .map_err(|e| GitError::PullFailed {
-                path: repo_path.to_path_buf(),
-                source: e,
-            })?;
+        self.fetch_remote(&repo, repo_path, &self.remote_name)?;
 
-        // Set up fetch options with appropriate authentication based on remote URL
-        let mut fetch_options = git2::FetchOptions::new();
-        let mut callbacks = git2::RemoteCallbacks::new();
+        let remote_branch_name = format!(
+            "{}/{branch_name}",
+            self.fetch_config.tracking_prefix(&self.remote_name)
+        );
+        let remote_ref = repo.find_reference(&remote_branch_name);
+        let Some(local) = local else {
+            return match remote_ref.ok().and_then(|reference| reference.target()) {
+                Some(new) => Ok(DryRunOutcome::FastForward {
+                    old: Oid::zero(),
+                    new,
+                }),
+                None => Err(GitError::UnbornBranch(repo_path.to_path_buf())),
+            };
+        };
 
-        // Get remote URL to determine authentication strategy
-        let remote_url = remote.url().unwrap_or("");
+        let remote_ref = remote_ref.map_err(pull_error)?;
+        let annotated_commit = repo
+            .reference_to_annotated_commit(&remote_ref)
+            .map_err(pull_error)?;
+        let analysis = repo.merge_analysis(&[&annotated_commit]).map_err(|e| {
+            repository::shallow_boundary_error(&repo, repo_path, "pull", e, pull_error)
+        })?;
 
-        if Self::is_https_url(remote_url) {
-            // Try HTTPS authentication (with PAT fallback)
-            if let Ok(credentials_callback) = Self::https_credentials_callback() {
-                callbacks.credentials(credentials_callback);
-            }
+        let remote = annotated_commit.id();
+        if analysis.0.is_fast_forward() {
+            Ok(DryRunOutcome::FastForward {
+                old: local,
+                new: remote,
+            })
+        } else if analysis.0.is_up_to_date() {
+            Ok(DryRunOutcome::UpToDate)
+        } else if analysis.0.is_normal() && self.merge_strategy == MergeStrategy::Merge {
+            Ok(DryRunOutcome::Merge { local, remote })
+        } else if analysis.0.is_normal() && self.merge_strategy == MergeStrategy::Rebase {
+            Ok(DryRunOutcome::Rebase { local, remote })
         } else {
-            // Use SSH authentication
-            let credentials_callback = self.ssh_config.credentials_callback()?;
-            callbacks.credentials(credentials_callback);
+            Err(GitError::MergeRequired {
+                path: repo_path.to_path_buf(),
+                local,
+                remote,
+            })
         }
+    }
 
-        fetch_options.remote_callbacks(callbacks);
+    /// Fetch the configured remote into its remote-tracking refs without touching HEAD or the working tree
+    ///
+    /// Uses the same fetch configuration and credentials as `pull`, so incoming changes can be
+    /// inspected (e.g. on `refs/remotes/origin/main`) before deciding to merge them.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn fetch(&self, repo_path: &Path) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
 
-        // Fetch all branches from remote
-        // Using empty slice fetches all configured refspecs (all branches)
-        remote
-            .fetch(&[] as &[&str], Some(&mut fetch_options), None)
-            .map_err(|e| GitError::PullFailed {
-                path: repo_path.to_path_buf(),
-                source: e,
-            })?;
+        self.fetch_remote(&repo, repo_path, &self.remote_name)?;
+        Ok(())
+    }
 
-        // Get the fetch head and merge
-        repo.fetchhead_foreach(|_ref_name, _remote_url, _oid, _is_merge| {
-            // Fetch head processing - we'll use this for more advanced merging later
-            true
-        })
-        .map_err(|e| GitError::PullFailed {
+    /// Pull only if the remote branch moved since the last fetch
+    ///
+    /// The remote's refs are listed first (like `git ls-remote`), and the fetch is skipped when
+    /// the tracked branch still matches the local remote-tracking ref.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository to update
+    pub fn pull_if_behind(&self, repo_path: &Path) -> Result<PullOutcome, GitError> {
+        self.pull_branch(repo_path, true, self.merge_strategy)
+    }
+
+    /// Fetch a single refspec and check out the fetched tip on `local_ref`, e.g. a PR head in CI
+    ///
+    /// The fetch uses the configured fetch settings. `local_ref` is created or moved to the
+    /// fetched tip before the working tree is updated, and moved back if the checkout fails.
+    /// Unless `force` is set, the checkout refuses to overwrite local modifications.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Remote to fetch from
+    /// * `refspec` - Refspec to fetch, e.g. `+refs/pull/42/head:refs/remotes/origin/pr/42`
+    /// * `local_ref` - Branch to check out the tip on, e.g. `pr-42` or `refs/heads/pr-42`
+    /// * `force` - Discard local modifications that are in the way instead of failing
+    ///
+    /// # Returns
+    /// The fetched commit
+    ///
+    /// # Errors
+    /// Returns `CheckoutFailed` without changing `local_ref` or HEAD if the checkout would
+    /// overwrite local modifications and `force` isn't set
+    pub fn fetch_and_checkout(
+        &self,
+        repo_path: &Path,
+        remote_name: &str,
+        refspec: &str,
+        local_ref: &str,
+        force: bool,
+    ) -> Result<Oid, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
             path: repo_path.to_path_buf(),
             source: e,
         })?;
 
-        // Get the remote branch reference that was just fetched
-        let remote_branch_name = format!("refs/remotes/origin/{branch_name}");
-        let remote_ref =
-            repo.find_reference(&remote_branch_name)
-                .map_err(|e| GitError::PullFailed {
-                    path: repo_path.to_path_buf(),
-                    source: e,
-                })?;
-
-        // Create annotated commit from the remote branch (not local HEAD)
-        let annotated_commit = repo
-            .reference_to_annotated_commit(&remote_ref)
-            .map_err(|e| GitError::PullFailed {
-                path: repo_path.to_path_buf(),
-                source: e,
-            })?;
+        let pull_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        };
 
-        // Perform the merge (fast-forward only for now)
-        let analysis =
-            repo.merge_analysis(&[&annotated_commit])
-                .map_err(|e| GitError::PullFailed {
-                    path: repo_path.to_path_buf(),
-                    source: e,
-                })?;
+        self.fetch_refspecs(&repo, repo_path, remote_name, &[refspec.to_string()])?;
 
-        if analysis.0.is_fast_forward() {
-            let refname = format!("refs/heads/{branch_name}");
-            let mut reference =
-                repo.find_reference(&refname)
-                    .map_err(|e| GitError::PullFailed {
-                        path: repo_path.to_path_buf(),
-                        source: e,
-                    })?;
+        // Without a destination the fetched tip is only recorded in FETCH_HEAD
+        let destination = refspec
+            .split_once(':')
+            .map(|(_, destination)| destination)
+            .filter(|destination| !destination.is_empty());
+        let tip = match destination {
+            Some(destination) => repo.refname_to_id(destination).map_err(pull_error)?,
+            None => {
+                let mut tip = None;
+                repo.fetchhead_foreach(|_ref_name, _remote_url, oid, _is_merge| {
+                    tip = Some(*oid);
+                    false
+                })
+                .or_else(|e| if tip.is_some() { Ok(()) } else { Err(e) })
+                .map_err(pull_error)?;
+                tip.ok_or_else(|| pull_error(git2::Error::from_str("Nothing was fetched")))?
+            }
+        };
 
-            reference
-                .set_target(annotated_commit.id(), "Fast-forward")
-                .map_err(|e| GitError::PullFailed {
-                    path: repo_path.to_path_buf(),
-                    source: e,
-                })?;
+        let local_ref = if local_ref.starts_with("refs/") {
+            local_ref.to_string()
+        } else {
+            format!("refs/heads/{local_ref}")
+        };
+        let checkout_error = |e: git2::Error| GitError::CheckoutFailed {
+            branch: local_ref.clone(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
 
-            repo.set_head(&refname).map_err(|e| GitError::PullFailed {
-                path: repo_path.to_path_buf(),
-                source: e,
-            })?;
+        let commit = repo.find_commit(tip).map_err(checkout_error)?;
+        let previous = repo.refname_to_id(&local_ref).ok();
+        let mut reference = repo
+            .reference(&local_ref, tip, true, &format!("fetch: {refspec}"))
+            .map_err(checkout_error)?;
 
-            repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
-                .map_err(|e| GitError::PullFailed {
-                    path: repo_path.to_path_buf(),
-                    source: e,
-                })?;
-        } else if analysis.0.is_up_to_date() {
-            // Already up to date, nothing to do
+        let mut checkout_builder = git2::build::CheckoutBuilder::default();
+        if force {
+            checkout_builder.force();
         } else {
-            return Err(GitError::MergeRequired(repo_path.to_path_buf()));
+            checkout_builder.safe();
         }
+        if let Err(e) = repo.checkout_tree(commit.as_object(), Some(&mut checkout_builder)) {
+            // Leave the branch where it was so it still matches the untouched working tree
+            let _ = match previous {
+                Some(previous) => reference
+                    .set_target(previous, "fetch: checkout failed")
+                    .map(|_| ()),
+                None => reference.delete(),
+            };
+            return Err(checkout_error(e));
+        }
+        repo.set_head(&local_ref).map_err(checkout_error)?;
 
-        Ok(())
+        Ok(tip)
     }
 
-    /// Get git config and check for credential helper configuration
-    fn get_git_config_with_credential_helpers() -> Result<git2::Config, git2::Error> {
-        let config = git2::Config::open_default().or_else(|_| git2::Config::new())?;
+    /// Check if `branch_name` can be fast-forwarded to its remote-tracking branch on the configured remote
+    ///
+    /// Uses the remote-tracking ref as of the last fetch; nothing is fetched.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch
+    ///
+    /// # Returns
+    /// `true` if the remote-tracking branch is strictly ahead of the local branch, `false`
+    /// if they're equal or have diverged
+    pub fn can_fast_forward(&self, repo_path: &Path, branch_name: &str) -> Result<bool, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let local = repo.refname_to_id(&format!("refs/heads/{branch_name}"))?;
+        let remote = repo.refname_to_id(&format!(
+            "{}/{branch_name}",
+            self.fetch_config.tracking_prefix(&self.remote_name)
+        ))?;
 
-        // Check if credential helpers are configured
-        let has_credential_helper = config.get_string("credential.helper").is_ok()
-            || config.entries(Some("credential\\..*\\.helper")).is_ok();
+        repo.graph_descendant_of(remote, local).map_err(|e| {
+            repository::shallow_boundary_error(
+                &repo,
+                repo_path,
+                "can_fast_forward",
+                e,
+                GitError::Git,
+            )
+        })
+    }
 
-        if !has_credential_helper {
-            eprintln!("Warning: No git credential helpers configured. Consider setting up a credential helper for better authentication:");
-            eprintln!("  git config --global credential.helper store");
-            eprintln!("  git config --global credential.helper cache");
-            eprintln!("  git config --global credential.helper osxkeychain  # macOS");
-            eprintln!("  git config --global credential.helper manager-core  # Cross-platform");
+    /// Fetch, then check if a pull of the current branch would be a fast-forward
+    ///
+    /// Like `can_fast_forward` for the checked out branch, but against a fresh fetch. Only the
+    /// remote-tracking refs are updated; the local branch and working tree are left alone.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `true` if the fetched branch is strictly ahead of the local branch, `false` if they're
+    /// equal or have diverged
+    pub fn fetch_can_fast_forward(&self, repo_path: &Path) -> Result<bool, GitError> {
+        match self.pull_dry_run(repo_path) {
+            Ok(DryRunOutcome::FastForward { .. }) => Ok(true),
+            Ok(_) | Err(GitError::MergeRequired { .. }) => Ok(false),
+            Err(e) => Err(e),
         }
+    }
+
+    /// Fetch `branch` from `remote_name` and merge it into HEAD as two separately reported steps
+    ///
+    /// Unlike `pull`, a diverged HEAD is joined with a merge commit instead of failing. If the
+    /// merge conflicts, nothing is changed, the fetched commit is left on the remote-tracking
+    /// branch and `MergeRequired` is returned.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Remote to fetch from
+    /// * `branch` - Name of the branch on the remote to merge
+    pub fn fetch_then_merge(
+        &self,
+        repo_path: &Path,
+        remote_name: &str,
+        branch: &str,
+    ) -> Result<(FetchSummary, MergeOutcome), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
 
-        Ok(config)
+        let pull_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        };
+
+        let remote_branch_name = format!(
+            "{}/{branch}",
+            self.fetch_config.tracking_prefix(remote_name)
+        );
+        let refspec = format!("+refs/heads/{branch}:{remote_branch_name}");
+
+        let summary = self.fetch_refspecs(&repo, repo_path, remote_name, &[refspec])?;
+        let remote = repo.find_remote(remote_name).map_err(pull_error)?;
+        // Like `git pull`, which keeps credentials in the URL out of the commit message
+        let remote_url = remote::strip_url_credentials(remote.url().unwrap_or(remote_name));
+
+        let fetched = repo
+            .find_reference(&remote_branch_name)
+            .and_then(|reference| repo.reference_to_annotated_commit(&reference))
+            .map_err(pull_error)?;
+        let outcome = merge::merge_into_head(
+            &repo,
+            repo_path,
+            "fetch_then_merge",
+            &fetched,
+            &remote_branch_name,
+            &format!("Merge branch '{branch}' of {remote_url}"),
+            self.signature.as_ref(),
+            pull_error,
+        )?;
+
+        Ok((summary, outcome))
     }
 
-    /// Create credentials callback for HTTPS authentication using Git credential manager
-    fn https_credentials_callback() -> Result<CredentialCallback, GitError> {
-        Ok(Box::new(
-            |url: &str, username_from_url: Option<&str>, allowed_types: git2::CredentialType| {
-                // Try git credential helper first
-                if allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
-                    if let Ok(config) = Self::get_git_config_with_credential_helpers() {
-                        if let Ok(cred) =
-                            git2::Cred::credential_helper(&config, url, username_from_url)
-                        {
-                            return Ok(cred);
-                        }
-                    }
-                }
+    /// Fetch every remote of the repository, like `git fetch --all`
+    ///
+    /// Each remote gets the credentials suited to its own URL. Remotes are fetched in
+    /// name order, and the first failure stops the rest.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// Each remote's name with its transfer statistics
+    pub fn fetch_all_remotes(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Vec<(String, FetchSummary)>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
 
-                // Fallback to environment variables for backward compatibility
-                if allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
-                    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-                        // For GitHub PAT, username can be anything (token is what matters)
-                        let username = username_from_url.unwrap_or("git");
-                        if let Ok(cred) = git2::Cred::userpass_plaintext(username, &token) {
-                            return Ok(cred);
-                        }
-                    }
+        let remote_names = repo.remotes().map_err(|e| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        })?;
 
-                    // Try alternative environment variable names
-                    if let Ok(token) = std::env::var("GH_TOKEN") {
-                        let username = username_from_url.unwrap_or("git");
-                        if let Ok(cred) = git2::Cred::userpass_plaintext(username, &token) {
-                            return Ok(cred);
-                        }
-                    }
+        remote_names
+            .iter()
+            .flatten()
+            .map(|remote_name| {
+                let summary = self.fetch_remote(&repo, repo_path, remote_name)?;
+                Ok((remote_name.to_string(), summary))
+            })
+            .collect()
+    }
 
-                    if let Ok(token) = std::env::var("GITHUB_ACCESS_TOKEN") {
-                        let username = username_from_url.unwrap_or("git");
-                        if let Ok(cred) = git2::Cred::userpass_plaintext(username, &token) {
-                            return Ok(cred);
-                        }
-                    }
-                }
+    /// Fetch the full history of a shallow repository, like `git fetch --unshallow`
+    ///
+    /// libgit2 1.7 drops the shallow boundary without fetching the missing commits, which
+    /// leaves the repository broken, so this runs `git fetch --unshallow` and requires the
+    /// `git` command line tool. Authentication is therefore git's own (credential helpers,
+    /// `ssh` and its agent) rather than this puller's SSH config and HTTPS token. Does nothing
+    /// if the repository isn't shallow.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Remote to fetch the missing history from
+    pub fn unshallow(&self, repo_path: &Path, remote_name: &str) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
 
-                // Try default credentials
-                if allowed_types.contains(git2::CredentialType::DEFAULT) {
-                    if let Ok(cred) = git2::Cred::default() {
-                        return Ok(cred);
-                    }
-                }
+        if !repo.is_shallow() {
+            return Ok(());
+        }
 
-                // If we get here, authentication failed
-                Err(git2::Error::from_str("No HTTPS credentials found. Configure git credential helper or set GITHUB_TOKEN environment variable for private repositories."))
-            },
-        ))
+        let refspecs = self
+            .fetch_config
+            .refspecs(remote_name, current_branch(&repo).as_deref());
+        let mut args = vec!["fetch", "--quiet", "--unshallow", remote_name];
+        args.extend(refspecs.iter().map(String::as_str));
+        command::run_git_checked(repo_path, &args)?;
+
+        Ok(())
     }
 
-    /// Check if URL is HTTPS
-    fn is_https_url(url: &str) -> bool {
-        url.starts_with("https://")
+    /// Update the current branch like `fetch_and_merge`, then its submodules if configured to
+    fn pull_branch(
+        &self,
+        repo_path: &Path,
+        skip_if_current: bool,
+        strategy: MergeStrategy,
+    ) -> Result<PullOutcome, GitError> {
+        let outcome = if self.autostash && strategy != MergeStrategy::FastForwardOnly {
+            self.with_autostash(repo_path, || {
+                self.fetch_and_merge(repo_path, skip_if_current, strategy)
+            })?
+        } else {
+            self.fetch_and_merge(repo_path, skip_if_current, strategy)?
+        };
+        if self.update_submodules {
+            self.update_submodules(repo_path, true)?;
+        }
+        Ok(outcome)
     }
-}
 
+    /// Run `pull` with local changes stashed, popping them afterwards whether or not it succeeded
+    fn with_autostash(
+        &self,
+        repo_path: &Path,
+        pull: impl FnOnce() -> Result<PullOutcome, GitError>,
+    ) -> Result<PullOutcome, GitError> {
+        let open = || {
+            Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+                path: repo_path.to_path_buf(),
+                source: e,
+            })
+        };
+        if !GitStash::has_changes(&open()?)? {
+            return pull();
+        }
+
+        GitStash::stash_save(repo_path, Some("git-ops autostash"))?;
+        let outcome = pull();
+        // Reopened so the index the pull wrote is read fresh
+        open()?
+            .stash_pop(0, None)
+            .map_err(|e| GitError::AutostashPopFailed {
+                path: repo_path.to_path_buf(),
+                source: e,
+            })?;
+        outcome
+    }
+
+    /// Initialize submodules and check out the commits the superproject records for them
+    ///
+    /// Like `git submodule update --init`. Submodule remotes are fetched with this puller's
+    /// credentials and proxy settings, chosen from each submodule's URL.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the superproject
+    /// * `recursive` - Also update submodules of submodules
+    ///
+    /// # Errors
+    /// Returns `SubmoduleUpdateFailed` naming the first submodule that couldn't be updated
+    pub fn update_submodules(&self, repo_path: &Path, recursive: bool) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        for mut submodule in repo.submodules()? {
+            let name = submodule.name().unwrap_or("").to_string();
+            let submodule_error = |e: git2::Error| GitError::SubmoduleUpdateFailed {
+                name: name.clone(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            };
+
+            // Initializing resolves relative URLs against the superproject's remote
+            submodule.init(false).map_err(submodule_error)?;
+            let url = repo
+                .config()
+                .and_then(|config| config.get_string(&format!("submodule.{name}.url")))
+                .ok()
+                .or_else(|| submodule.url().map(str::to_string))
+                .unwrap_or_default();
+
+            let callbacks = auth::remote_callbacks(
+                &self.ssh_config,
+                &url,
+                self.https_token.as_deref(),
+                &self.token_auth,
+            )?;
+            let mut fetch_options = git2::FetchOptions::new();
+            fetch_options.remote_callbacks(callbacks);
+            fetch_options.proxy_options(remote::proxy_options(self.proxy.as_deref()));
+            let mut update_options = git2::SubmoduleUpdateOptions::new();
+            update_options.fetch(fetch_options);
+            submodule
+                .update(false, Some(&mut update_options))
+                .map_err(submodule_error)?;
+
+            if recursive {
+                if let Some(workdir) = submodule.open().map_err(submodule_error)?.workdir() {
+                    self.update_submodules(workdir, true)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch into a bare repository, reporting how the branch HEAD names moved
+    fn fetch_bare(&self, repo: &Repository, repo_path: &Path) -> Result<PullOutcome, GitError> {
+        let old = repo.refname_to_id("HEAD").ok();
+        self.fetch_remote(repo, repo_path, &self.remote_name)?;
+        let new = repo.refname_to_id("HEAD").ok();
+
+        match new {
+            Some(new) if old != Some(new) => Ok(PullOutcome::FastForwarded {
+                old: old.unwrap_or_else(Oid::zero),
+                new,
+            }),
+            _ => Ok(PullOutcome::UpToDate),
+        }
+    }
+
+    /// Fetch and create the unborn branch HEAD names at its remote-tracking branch, then check it out
+    fn pull_into_unborn(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+    ) -> Result<PullOutcome, GitError> {
+        let pull_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        };
+
+        let head = repo.find_reference("HEAD").map_err(pull_error)?;
+        let branch_name = head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?
+            .to_string();
+
+        self.fetch_remote(repo, repo_path, &self.remote_name)?;
+
+        let remote_branch_name = format!(
+            "{}/{branch_name}",
+            self.fetch_config.tracking_prefix(&self.remote_name)
+        );
+        let Ok(remote_commit) = repo
+            .find_reference(&remote_branch_name)
+            .and_then(|reference| reference.peel_to_commit())
+        else {
+            return Err(GitError::UnbornBranch(repo_path.to_path_buf()));
+        };
+
+        let mut checkout_builder = git2::build::CheckoutBuilder::default();
+        if self.force_checkout {
+            checkout_builder.force();
+        } else {
+            checkout_builder.safe();
+        }
+        if let Some(notifier) = &self.checkout_notifier {
+            notifier.apply(&mut checkout_builder);
+        }
+        repo.checkout_tree(remote_commit.as_object(), Some(&mut checkout_builder))
+            .map_err(|e| {
+                if e.code() == git2::ErrorCode::Conflict {
+                    GitError::DirtyWorkingTree {
+                        path: repo_path.to_path_buf(),
+                        source: e,
+                    }
+                } else {
+                    pull_error(e)
+                }
+            })?;
+        repo.reference(
+            &format!("refs/heads/{branch_name}"),
+            remote_commit.id(),
+            false,
+            &format!("pull: created from {remote_branch_name}"),
+        )
+        .map_err(pull_error)?;
+
+        Ok(PullOutcome::FastForwarded {
+            old: Oid::zero(),
+            new: remote_commit.id(),
+        })
+    }
+
+    /// Fetch (unless `skip_if_current` and the remote hasn't moved) and update the current branch
+    fn fetch_and_merge(
+        &self,
+        repo_path: &Path,
+        skip_if_current: bool,
+        strategy: MergeStrategy,
+    ) -> Result<PullOutcome, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        // A bare repository such as a mirror has no working tree; fetching updates its refs
+        if repo.is_bare() {
+            return self.fetch_bare(&repo, repo_path);
+        }
+
+        // Get the current branch
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+                return self.pull_into_unborn(&repo, repo_path);
+            }
+            Err(e) => {
+                return Err(GitError::PullFailed {
+                    path: repo_path.to_path_buf(),
+                    url: None,
+                    source: e,
+                })
+            }
+        };
+
+        let branch_name = head
+            .shorthand()
+            .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+
+        let remote_branch_name = format!(
+            "{}/{branch_name}",
+            self.fetch_config.tracking_prefix(&self.remote_name)
+        );
+
+        let remote_is_current = skip_if_current
+            && self.remote_matches_tracking_ref(
+                &repo,
+                repo_path,
+                branch_name,
+                &remote_branch_name,
+            )?;
+
+        if !remote_is_current {
+            self.fetch_remote(&repo, repo_path, &self.remote_name)?;
+
+            // Get the fetch head and merge
+            repo.fetchhead_foreach(|_ref_name, _remote_url, _oid, _is_merge| {
+                // Fetch head processing - we'll use this for more advanced merging later
+                true
+            })
+            .map_err(|e| GitError::PullFailed {
+                path: repo_path.to_path_buf(),
+                url: None,
+                source: e,
+            })?;
+        }
+
+        // Get the remote branch reference that was just fetched
+        let remote_ref =
+            repo.find_reference(&remote_branch_name)
+                .map_err(|e| GitError::PullFailed {
+                    path: repo_path.to_path_buf(),
+                    url: None,
+                    source: e,
+                })?;
+
+        // Create annotated commit from the remote branch (not local HEAD)
+        let annotated_commit = repo
+            .reference_to_annotated_commit(&remote_ref)
+            .map_err(|e| GitError::PullFailed {
+                path: repo_path.to_path_buf(),
+                url: None,
+                source: e,
+            })?;
+
+        // Perform the merge (fast-forward only for now)
+        let analysis = repo.merge_analysis(&[&annotated_commit]).map_err(|e| {
+            repository::shallow_boundary_error(&repo, repo_path, "pull", e, |e| {
+                GitError::PullFailed {
+                    path: repo_path.to_path_buf(),
+                    url: None,
+                    source: e,
+                }
+            })
+        })?;
+        debug!(
+            "Merge analysis of {branch_name} against {remote_branch_name}: {:?}",
+            analysis.0
+        );
+
+        if analysis.0.is_fast_forward() {
+            let old = head
+                .target()
+                .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+            let refname = format!("refs/heads/{branch_name}");
+            let mut reference =
+                repo.find_reference(&refname)
+                    .map_err(|e| GitError::PullFailed {
+                        path: repo_path.to_path_buf(),
+                        url: None,
+                        source: e,
+                    })?;
+
+            // Update the working tree before moving the branch, so a refused checkout leaves both as they were
+            let target = repo.find_object(annotated_commit.id(), None).map_err(|e| {
+                GitError::PullFailed {
+                    path: repo_path.to_path_buf(),
+                    url: None,
+                    source: e,
+                }
+            })?;
+            let mut checkout_builder = git2::build::CheckoutBuilder::default();
+            if self.force_checkout {
+                checkout_builder.force();
+            } else {
+                checkout_builder.safe();
+            }
+            if let Some(notifier) = &self.checkout_notifier {
+                notifier.apply(&mut checkout_builder);
+            }
+            repo.checkout_tree(&target, Some(&mut checkout_builder))
+                .map_err(|e| {
+                    if e.code() == git2::ErrorCode::Conflict {
+                        GitError::DirtyWorkingTree {
+                            path: repo_path.to_path_buf(),
+                            source: e,
+                        }
+                    } else {
+                        GitError::PullFailed {
+                            path: repo_path.to_path_buf(),
+                            url: None,
+                            source: e,
+                        }
+                    }
+                })?;
+
+            reference
+                .set_target(annotated_commit.id(), "Fast-forward")
+                .map_err(|e| GitError::PullFailed {
+                    path: repo_path.to_path_buf(),
+                    url: None,
+                    source: e,
+                })?;
+
+            repo.set_head(&refname).map_err(|e| GitError::PullFailed {
+                path: repo_path.to_path_buf(),
+                url: None,
+                source: e,
+            })?;
+
+            Ok(PullOutcome::FastForwarded {
+                old,
+                new: annotated_commit.id(),
+            })
+        } else if analysis.0.is_up_to_date() {
+            Ok(PullOutcome::UpToDate)
+        } else if analysis.0.is_normal() && strategy == MergeStrategy::Merge {
+            let pull_error = |e: git2::Error| GitError::PullFailed {
+                path: repo_path.to_path_buf(),
+                url: None,
+                source: e,
+            };
+            let remote_short_name = remote_branch_name
+                .strip_prefix("refs/remotes/")
+                .unwrap_or(&remote_branch_name);
+            let outcome = merge::merge_into_head(
+                &repo,
+                repo_path,
+                "pull",
+                &annotated_commit,
+                &remote_branch_name,
+                &format!("Merge remote-tracking branch '{remote_short_name}'"),
+                self.signature.as_ref(),
+                pull_error,
+            )?;
+            match outcome {
+                MergeOutcome::Merged { commit } => Ok(PullOutcome::Merged { commit }),
+                MergeOutcome::FastForwarded { from, to } => {
+                    Ok(PullOutcome::FastForwarded { old: from, new: to })
+                }
+                MergeOutcome::UpToDate => Ok(PullOutcome::UpToDate),
+            }
+        } else if analysis.0.is_normal() && strategy == MergeStrategy::Rebase {
+            let head = self.replay_onto(&repo, repo_path, &annotated_commit)?;
+            Ok(PullOutcome::Rebased { head })
+        } else {
+            let local = head
+                .target()
+                .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+            Err(GitError::MergeRequired {
+                path: repo_path.to_path_buf(),
+                local,
+                remote: annotated_commit.id(),
+            })
+        }
+    }
+
+    /// Replay the commits on HEAD that `upstream` lacks onto `upstream`, returning the new HEAD
+    ///
+    /// Any failure aborts the rebase, restoring the branch and working tree. A conflicting
+    /// commit returns `MergeRequired`.
+    fn replay_onto(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+        upstream: &git2::AnnotatedCommit,
+    ) -> Result<Oid, GitError> {
+        let pull_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        };
+
+        let local = repo
+            .head()
+            .map_err(pull_error)?
+            .target()
+            .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+        let signature =
+            author::commit_signature(repo, self.signature.as_ref()).map_err(pull_error)?;
+        let mut rebase = repo
+            .rebase(None, Some(upstream), None, None)
+            .map_err(pull_error)?;
+
+        let replayed = (|| {
+            while let Some(operation) = rebase.next() {
+                operation.map_err(pull_error)?;
+                let has_conflicts = repo.index().map_err(pull_error)?.has_conflicts();
+                if has_conflicts {
+                    return Err(GitError::MergeRequired {
+                        path: repo_path.to_path_buf(),
+                        local,
+                        remote: upstream.id(),
+                    });
+                }
+                match rebase.commit(None, &signature, None) {
+                    Ok(_) => {}
+                    // The commit's changes are already upstream, so there's nothing to replay
+                    Err(e) if e.code() == git2::ErrorCode::Applied => {}
+                    Err(e) => return Err(pull_error(e)),
+                }
+            }
+            rebase.finish(Some(&signature)).map_err(pull_error)
+        })();
+        if let Err(e) = replayed {
+            // Never leave a half-finished rebase (and a detached HEAD) behind
+            let _ = rebase.abort();
+            return Err(e);
+        }
+
+        let head = repo
+            .head()
+            .map_err(pull_error)?
+            .target()
+            .ok_or_else(|| GitError::InvalidBranch(repo_path.to_path_buf()))?;
+        Ok(head)
+    }
+
+    /// Rebase the current branch onto `upstream_rev`, like `git rebase <upstream>`
+    ///
+    /// The local commits `upstream_rev` lacks are replayed on top of it, each committed with
+    /// the configured signature. Nothing is fetched, so fetch first to rebase onto the
+    /// latest remote branch.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `upstream_rev` - Revision to rebase onto, e.g. `origin/main`
+    ///
+    /// # Returns
+    /// The new tip of the current branch
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `upstream_rev` doesn't resolve to a commit, and
+    /// `MergeRequired` if a commit conflicts, in which case the rebase is aborted and the
+    /// branch left as it was
+    pub fn rebase_onto(&self, repo_path: &Path, upstream_rev: &str) -> Result<Oid, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let upstream = repo
+            .revparse_single(upstream_rev)
+            .and_then(|object| object.peel_to_commit())
+            .and_then(|commit| repo.find_annotated_commit(commit.id()))
+            .map_err(|e| GitError::RevisionNotFound {
+                rev: upstream_rev.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            })?;
+
+        self.replay_onto(&repo, repo_path, &upstream)
+    }
+
+    /// Hard-reset a local branch to its remote-tracking branch, discarding local changes
+    ///
+    /// If the branch is checked out, the index and working tree are reset too; otherwise only
+    /// the branch ref is moved (and created if missing).
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `branch_name` - Name of the local branch to reset
+    /// * `fetch` - Whether to fetch from the remote before resetting
+    pub fn reset_to_remote(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        fetch: bool,
+    ) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        if fetch {
+            self.fetch_remote(&repo, repo_path, &self.remote_name)?;
+        }
+
+        let remote_branch_name = format!(
+            "{}/{branch_name}",
+            self.fetch_config.tracking_prefix(&self.remote_name)
+        );
+        let reset_error = |e: git2::Error| GitError::ResetFailed {
+            target: remote_branch_name.clone(),
+            path: repo_path.to_path_buf(),
+            source: e,
+        };
+
+        let remote_commit = repo
+            .find_reference(&remote_branch_name)
+            .and_then(|reference| reference.peel_to_commit())
+            .map_err(reset_error)?;
+
+        let local_branch_name = format!("refs/heads/{branch_name}");
+        let is_checked_out = repo
+            .head()
+            .map(|head| head.name() == Some(local_branch_name.as_str()))
+            .unwrap_or(false);
+
+        if is_checked_out {
+            repo.reset(remote_commit.as_object(), git2::ResetType::Hard, None)
+                .map_err(reset_error)?;
+        } else {
+            repo.reference(
+                &local_branch_name,
+                remote_commit.id(),
+                true,
+                &format!("reset: moving to {remote_branch_name}"),
+            )
+            .map_err(reset_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// List the configured remote's refs and check if `branch_name` still points where the tracking ref does
+    fn remote_matches_tracking_ref(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+        branch_name: &str,
+        remote_branch_name: &str,
+    ) -> Result<bool, GitError> {
+        let pull_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        };
+
+        let Ok(tracking_oid) = repo.refname_to_id(remote_branch_name) else {
+            return Ok(false);
+        };
+
+        let mut remote = repo.find_remote(&self.remote_name).map_err(pull_error)?;
+        let remote_url = remote.url().unwrap_or("").to_string();
+        let connect_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: Some(remote::strip_url_credentials(&remote_url)),
+            source: e,
+        };
+        let callbacks = auth::remote_callbacks(
+            &self.ssh_config,
+            &remote_url,
+            self.https_token.as_deref(),
+            &self.token_auth,
+        )?;
+        let connection = remote
+            .connect_auth(
+                Direction::Fetch,
+                Some(callbacks),
+                Some(remote::proxy_options(self.proxy.as_deref())),
+            )
+            .map_err(connect_error)?;
+
+        let branch_ref = format!("refs/heads/{branch_name}");
+        let remote_oid = connection
+            .list()
+            .map_err(connect_error)?
+            .iter()
+            .find(|head| head.name() == branch_ref)
+            .map(|head| head.oid());
+
+        Ok(remote_oid == Some(tracking_oid))
+    }
+
+    /// Fetch from `remote_name` using the configured fetch settings and credentials
+    fn fetch_remote(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+        remote_name: &str,
+    ) -> Result<FetchSummary, GitError> {
+        let refspecs = self
+            .fetch_config
+            .refspecs(remote_name, current_branch(repo).as_deref());
+        self.fetch_refspecs(repo, repo_path, remote_name, &refspecs)
+    }
+
+    /// Fetch `refspecs` from `remote_name` using the configured fetch settings and credentials
+    ///
+    /// An empty list fetches the remote's configured refspecs.
+    fn fetch_refspecs(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+        remote_name: &str,
+        refspecs: &[String],
+    ) -> Result<FetchSummary, GitError> {
+        let pull_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: None,
+            source: e,
+        };
+
+        // libgit2 1.7 drops the existing shallow roots when fetching into a shallow
+        // repository and reloads them only on the handle that fetched. Fetch through a
+        // separate handle so the corrected roots are what `repo` keeps seeing.
+        let shallow_roots = fetch::shallow_roots(repo).map_err(pull_error)?;
+        let fetch_repo;
+        let repo = if shallow_roots.is_empty() {
+            repo
+        } else {
+            fetch_repo = Repository::open(repo.path()).map_err(pull_error)?;
+            &fetch_repo
+        };
+
+        let mut remote = repo.find_remote(remote_name).map_err(pull_error)?;
+        let remote_url = remote.url().unwrap_or("").to_string();
+        let fetch_error = |e: git2::Error| GitError::PullFailed {
+            path: repo_path.to_path_buf(),
+            url: Some(remote::strip_url_credentials(&remote_url)),
+            source: e,
+        };
+
+        // Set up fetch options with appropriate authentication based on remote URL
+        let mut fetch_options = git2::FetchOptions::new();
+        let mut callbacks = auth::remote_callbacks(
+            &self.ssh_config,
+            &remote_url,
+            self.https_token.as_deref(),
+            &self.token_auth,
+        )?;
+        progress::report_transfer(
+            &mut callbacks,
+            self.progress.as_ref(),
+            self.progress_interval,
+            self.cancel.as_ref(),
+        );
+        fetch_options.remote_callbacks(callbacks);
+        fetch_options.proxy_options(remote::proxy_options(self.proxy.as_deref()));
+        self.fetch_config.apply_depth(&mut fetch_options);
+
+        // Advertise any known tips so the server can send less
+        let _negotiation_tips =
+            NegotiationTips::create(repo, self.fetch_config.have_tips()).map_err(pull_error)?;
+
+        debug!(
+            "Fetching {remote_name} ({}) into {} with refspecs {refspecs:?}",
+            remote::strip_url_credentials(&remote_url),
+            repo_path.display()
+        );
+        // An empty refspec list fetches all configured refspecs (all branches)
+        self.retry_policy
+            .run(|| remote.fetch(refspecs, Some(&mut fetch_options), None))
+            .map_err(|e| {
+                // An aborted transfer surfaces as a callback error, or as `User` on some transports
+                let aborted =
+                    e.class() == git2::ErrorClass::Callback || e.code() == git2::ErrorCode::User;
+                if aborted && progress::is_cancelled(self.cancel.as_ref()) {
+                    GitError::Cancelled {
+                        path: repo_path.to_path_buf(),
+                    }
+                } else {
+                    fetch_error(e)
+                }
+            })?;
+
+        if !shallow_roots.is_empty() {
+            fetch::keep_shallow_roots(repo, &shallow_roots).map_err(pull_error)?;
+        }
+
+        fetch::prune_excluded_refs(repo, &self.fetch_config, remote_name).map_err(pull_error)?;
+
+        if self.fetch_config.update_head() {
+            fetch::update_head_symref(
+                repo,
+                &remote,
+                &self.fetch_config.tracking_prefix(remote_name),
+            )
+            .map_err(fetch_error)?;
+        }
+
+        let summary = FetchSummary::from(remote.stats());
+        debug!(
+            "Fetched {remote_name} into {}: {} of {} objects received, {} bytes",
+            repo_path.display(),
+            summary.received_objects,
+            summary.total_objects,
+            summary.received_bytes
+        );
+        Ok(summary)
+    }
+}
+
+/// Name of the checked-out branch, `None` if HEAD is detached or unborn
+fn current_branch(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    head.shorthand().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::FetchScope;
+    use crate::test_support::{
+        commit_file, init_repo, shallow_clone, upstream_and_clone, GitDaemon, GitHttpServer,
+    };
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn test_puller() -> GitPuller {
+        GitPuller::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ))
+    }
+
+    #[test]
+    fn test_pull_fast_forward() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        test_puller().pull(local.workdir().unwrap()).unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+    }
+
+    /// Stage an edit to README.md in `local` that conflicts with a new upstream commit
+    fn conflicting_local_edit(upstream: &Repository, local: &Repository) {
+        commit_file(upstream, "README.md", "upstream\n", "Update readme");
+        std::fs::write(local.workdir().unwrap().join("README.md"), "local\n").unwrap();
+        let mut index = local.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+    }
+
+    /// Add `root/library`, with one commit, as submodule `library` of `upstream`
+    fn add_library_submodule(root: &Path, upstream: &Repository) -> Repository {
+        let library = init_repo(&root.join("library"));
+        commit_file(&library, "lib.txt", "v1\n", "Library v1");
+
+        let library_url = root.join("library").to_str().unwrap().to_string();
+        let mut submodule = upstream
+            .submodule(&library_url, Path::new("library"), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        commit_index(upstream, "Add library");
+        library
+    }
+
+    /// Commit whatever is staged in `repo` on HEAD
+    fn commit_index(repo: &Repository, message: &str) {
+        let signature = repo.signature().unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_update_submodules_checks_out_recorded_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        add_library_submodule(temp_dir.path(), &upstream);
+        test_puller().pull(local.workdir().unwrap()).unwrap();
+        let library_file = local.workdir().unwrap().join("library/lib.txt");
+        assert!(!library_file.exists());
+
+        test_puller()
+            .update_submodules(local.workdir().unwrap(), true)
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(library_file).unwrap(), "v1\n");
+    }
+
+    #[test]
+    fn test_pull_with_update_submodules_moves_submodule() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let library = add_library_submodule(temp_dir.path(), &upstream);
+        let mut puller = test_puller();
+        puller.set_update_submodules(true);
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        let v2 = commit_file(&library, "lib.txt", "v2\n", "Library v2");
+        let mut submodule = upstream.find_submodule("library").unwrap();
+        let upstream_library = submodule.open().unwrap();
+        upstream_library
+            .find_remote("origin")
+            .unwrap()
+            .fetch(&["main"], None, None)
+            .unwrap();
+        let v2_object = upstream_library.find_object(v2, None).unwrap();
+        upstream_library
+            .reset(&v2_object, git2::ResetType::Hard, None)
+            .unwrap();
+        submodule.add_to_index(true).unwrap();
+        commit_index(&upstream, "Bump library");
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        let local_library = Repository::open(local.workdir().unwrap().join("library")).unwrap();
+        assert_eq!(local_library.head().unwrap().target(), Some(v2));
+        assert_eq!(
+            std::fs::read_to_string(local.workdir().unwrap().join("library/lib.txt")).unwrap(),
+            "v2\n"
+        );
+    }
+
+    #[test]
+    fn test_pull_refuses_to_overwrite_local_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_head = local.head().unwrap().target();
+        conflicting_local_edit(&upstream, &local);
+
+        let result = test_puller().pull(local.workdir().unwrap());
+
+        assert!(matches!(result, Err(GitError::DirtyWorkingTree { .. })));
+        assert_eq!(local.head().unwrap().target(), local_head);
+        assert_eq!(
+            std::fs::read_to_string(local.workdir().unwrap().join("README.md")).unwrap(),
+            "local\n"
+        );
+    }
+
+    #[test]
+    fn test_pull_reports_conflicting_files_to_checkout_notifier() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        conflicting_local_edit(&upstream, &local);
+        let notified = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = notified.clone();
+        let mut puller = test_puller();
+        puller.set_checkout_notifier(Some(CheckoutNotifier::new(move |notification, path| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((notification, path.to_path_buf()));
+        })));
+
+        let result = puller.pull(local.workdir().unwrap());
+
+        assert!(matches!(result, Err(GitError::DirtyWorkingTree { .. })));
+        assert_eq!(
+            *notified.lock().unwrap(),
+            vec![(
+                crate::checkout::CheckoutNotification::Conflict,
+                std::path::PathBuf::from("README.md")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_pull_with_force_checkout_discards_local_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        conflicting_local_edit(&upstream, &local);
+        let mut puller = test_puller();
+        puller.set_force_checkout(true);
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(local.workdir().unwrap().join("README.md")).unwrap(),
+            "upstream\n"
+        );
+    }
+
+    /// A proxy that refuses one connection with 502, returning its URL and the request line it got
+    fn recording_proxy() -> (String, std::sync::mpsc::Receiver<String>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request_line = String::new();
+            BufReader::new(&stream)
+                .read_line(&mut request_line)
+                .unwrap();
+            let _ = stream.write_all(
+                b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+            sender.send(request_line).unwrap();
+        });
+        (url, receiver)
+    }
+
+    #[test]
+    fn test_fetch_goes_through_configured_proxy() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_upstream, local) = upstream_and_clone(temp_dir.path());
+        local
+            .remote_set_url("origin", "https://git.example.invalid/repo.git")
+            .unwrap();
+        let (proxy_url, requests) = recording_proxy();
+        let mut puller = test_puller();
+        puller.set_proxy(Some(proxy_url));
+
+        let result = puller.fetch(local.workdir().unwrap());
+
+        assert!(matches!(result, Err(GitError::PullFailed { .. })));
+        let request_line = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(request_line.starts_with("CONNECT git.example.invalid:443 "));
+    }
+
+    #[test]
+    fn test_fetch_detects_proxy_from_git_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_upstream, local) = upstream_and_clone(temp_dir.path());
+        local
+            .remote_set_url("origin", "https://git.example.invalid/repo.git")
+            .unwrap();
+        let (proxy_url, requests) = recording_proxy();
+        local
+            .config()
+            .unwrap()
+            .set_str("http.proxy", &proxy_url)
+            .unwrap();
+
+        let result = test_puller().fetch(local.workdir().unwrap());
+
+        assert!(result.is_err());
+        let request_line = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(request_line.starts_with("CONNECT git.example.invalid:443 "));
+    }
+
+    #[test]
+    fn test_pull_into_unborn_branch_creates_it_from_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream = init_repo(&temp_dir.path().join("upstream"));
+        let upstream_commit = commit_file(&upstream, "README.md", "initial\n", "Initial commit");
+        let local_path = temp_dir.path().join("local");
+        let local = init_repo(&local_path);
+        local
+            .remote("origin", temp_dir.path().join("upstream").to_str().unwrap())
+            .unwrap();
+
+        let outcome = test_puller().pull_detailed(&local_path).unwrap();
+
+        assert_eq!(
+            outcome,
+            PullOutcome::FastForwarded {
+                old: Oid::zero(),
+                new: upstream_commit,
+            }
+        );
+        assert_eq!(local.head().unwrap().name(), Some("refs/heads/main"));
+        assert_eq!(local.head().unwrap().target(), Some(upstream_commit));
+        assert_eq!(
+            std::fs::read_to_string(local_path.join("README.md")).unwrap(),
+            "initial\n"
+        );
+    }
+
+    #[test]
+    fn test_pull_into_unborn_branch_missing_on_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream = init_repo(&temp_dir.path().join("upstream"));
+        commit_file(&upstream, "README.md", "initial\n", "Initial commit");
+        let local_path = temp_dir.path().join("local");
+        let local = init_repo(&local_path);
+        local
+            .remote("origin", temp_dir.path().join("upstream").to_str().unwrap())
+            .unwrap();
+        local.set_head("refs/heads/develop").unwrap();
+
+        let result = test_puller().pull(&local_path);
+
+        assert!(matches!(result, Err(GitError::UnbornBranch(path)) if path == local_path));
+    }
+
+    #[test]
+    fn test_pull_dry_run_leaves_branch_and_working_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let old = local.head().unwrap().target().unwrap();
+        let new = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        let outcome = test_puller()
+            .pull_dry_run(local.workdir().unwrap())
+            .unwrap();
+
+        assert_eq!(outcome, DryRunOutcome::FastForward { old, new });
+        assert_eq!(local.head().unwrap().target(), Some(old));
+        assert!(!local.workdir().unwrap().join("file.txt").exists());
+        assert_eq!(
+            local.refname_to_id("refs/remotes/origin/main").unwrap(),
+            new
+        );
+    }
+
+    #[test]
+    fn test_pull_dry_run_reports_merge_for_diverged_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let remote_commit = commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local change");
+        let mut puller = test_puller();
+
+        let fast_forward_only = puller.pull_dry_run(local.workdir().unwrap());
+        puller.set_merge_strategy(MergeStrategy::Merge);
+        let merge = puller.pull_dry_run(local.workdir().unwrap()).unwrap();
+        puller.set_merge_strategy(MergeStrategy::Rebase);
+        let rebase = puller.pull_dry_run(local.workdir().unwrap()).unwrap();
+
+        assert!(matches!(
+            fast_forward_only,
+            Err(GitError::MergeRequired { local, .. }) if local == local_commit
+        ));
+        assert_eq!(
+            merge,
+            DryRunOutcome::Merge {
+                local: local_commit,
+                remote: remote_commit
+            }
+        );
+        assert_eq!(
+            rebase,
+            DryRunOutcome::Rebase {
+                local: local_commit,
+                remote: remote_commit
+            }
+        );
+        assert_eq!(local.head().unwrap().target(), Some(local_commit));
+    }
+
+    #[test]
+    fn test_pull_detailed_reports_fast_forward_and_up_to_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let old_commit = local.head().unwrap().target().unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let puller = test_puller();
+
+        let first = puller.pull_detailed(local.workdir().unwrap()).unwrap();
+        let second = puller.pull_detailed(local.workdir().unwrap()).unwrap();
+
+        assert_eq!(
+            first,
+            PullOutcome::FastForwarded {
+                old: old_commit,
+                new: new_commit
+            }
+        );
+        assert_eq!(second, PullOutcome::UpToDate);
+    }
+
+    #[test]
+    fn test_fetch_failure_reports_remote_url() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_upstream, local) = upstream_and_clone(temp_dir.path());
+        let missing = temp_dir.path().join("missing");
+        local
+            .remote_set_url("origin", missing.to_str().unwrap())
+            .unwrap();
+
+        let result = test_puller().fetch(local.workdir().unwrap());
+
+        match result {
+            Err(GitError::PullFailed { url, .. }) => {
+                assert_eq!(url.as_deref(), missing.to_str());
+            }
+            other => panic!("expected PullFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_fetch_reports_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        commit_file(&upstream, "file.txt", "update\n", "Update");
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = reports.clone();
+        let mut puller = test_puller();
+        puller.set_progress_callback(move |progress| recorded.lock().unwrap().push(progress));
+
+        puller.fetch(local.workdir().unwrap()).unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert!(!reports.is_empty());
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].received_objects <= pair[1].received_objects));
+        assert!(reports.last().unwrap().received_objects > 0);
+    }
+
+    #[test]
+    fn test_pull_with_cancel_aborts_fetch() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_head = local.head().unwrap().target();
+        commit_file(&upstream, "file.txt", "update\n", "Update");
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        let mut puller = test_puller();
+        // Cancel from inside the transfer, as another thread would while it runs
+        puller
+            .set_progress_callback(move |_| flag.store(true, std::sync::atomic::Ordering::SeqCst));
+
+        let result = puller.pull_with_cancel(local.workdir().unwrap(), cancel);
+
+        assert!(
+            matches!(result, Err(GitError::Cancelled { .. })),
+            "{result:?}"
+        );
+        assert_eq!(local.head().unwrap().target(), local_head);
+    }
+
+    #[test]
+    fn test_have_tips_are_sent_as_haves() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream = init_repo(&temp_dir.path().join("upstream"));
+        for index in 0..5 {
+            commit_file(&upstream, "file.txt", &format!("{index}\n"), "Change");
+        }
+        let last_synced = upstream.head().unwrap().target().unwrap();
+        let daemon = GitDaemon::serve(temp_dir.path());
+        // Repositories that hold the synced history but no refs pointing into it
+        let detached_clone = |name: &str| {
+            let path = temp_dir.path().join(name);
+            let repo = init_repo(&path);
+            repo.remote("origin", &daemon.url("upstream")).unwrap();
+            repo.find_remote("origin")
+                .unwrap()
+                .fetch(&["+refs/heads/main:refs/synced"], None, None)
+                .unwrap();
+            for reference in repo.references().unwrap() {
+                reference.unwrap().delete().unwrap();
+            }
+            (repo, path)
+        };
+        let (without_tips, without_tips_path) = detached_clone("without-tips");
+        let (with_tips, with_tips_path) = detached_clone("with-tips");
+        commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut fetch_config = FetchConfig::new();
+        fetch_config.set_have_tips(vec![last_synced]);
+        let mut tips_puller = test_puller();
+        tips_puller.set_fetch_config(fetch_config);
+
+        let full = test_puller()
+            .fetch_remote(&without_tips, &without_tips_path, "origin")
+            .unwrap();
+        let incremental = tips_puller
+            .fetch_remote(&with_tips, &with_tips_path, "origin")
+            .unwrap();
+
+        // The new commit, its tree and its blob
+        assert_eq!(incremental.received_objects, 3);
+        assert!(full.received_objects > incremental.received_objects);
+    }
+
+    #[test]
+    fn test_fetch_leaves_local_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_head = local.head().unwrap().target();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        test_puller().fetch(local.workdir().unwrap()).unwrap();
+
+        assert_eq!(
+            local.refname_to_id("refs/remotes/origin/main").unwrap(),
+            new_commit
+        );
+        assert_eq!(local.head().unwrap().target(), local_head);
+        assert!(!local.workdir().unwrap().join("file.txt").exists());
+    }
+
+    #[test]
+    fn test_pull_from_remote_named_upstream() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        local.remote_rename("origin", "upstream").unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut puller = test_puller();
+        puller.set_remote_name("upstream".to_string());
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+        let default_remote = test_puller().pull(local.workdir().unwrap());
+
+        assert_eq!(
+            local.refname_to_id("refs/remotes/upstream/main").unwrap(),
+            new_commit
+        );
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+        assert!(matches!(default_remote, Err(GitError::PullFailed { .. })));
+    }
+
+    #[test]
+    fn test_reset_to_remote_named_upstream() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        local.remote_rename("origin", "upstream").unwrap();
+        commit_file(&local, "local.txt", "local\n", "Local only");
+        let upstream_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut puller = test_puller();
+        puller.set_remote_name("upstream".to_string());
+
+        puller
+            .reset_to_remote(local.workdir().unwrap(), "main", true)
+            .unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(upstream_commit));
+    }
+
+    #[test]
+    fn test_pull_into_namespace() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut fetch_config = FetchConfig::new();
+        fetch_config.set_namespace(Some("mirror".to_string()));
+        let mut puller = test_puller();
+        puller.set_fetch_config(fetch_config);
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        let namespaced = local
+            .find_reference("refs/namespaces/mirror/refs/remotes/origin/main")
+            .unwrap();
+        assert_eq!(namespaced.target(), Some(new_commit));
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+    }
+
+    #[test]
+    fn test_pull_current_branch_scope_leaves_other_branches_stale() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let old_commit = upstream.head().unwrap().peel_to_commit().unwrap();
+        upstream.branch("other", &old_commit, false).unwrap();
+        test_puller().fetch(local.workdir().unwrap()).unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        upstream
+            .reference("refs/heads/other", new_commit, true, "move other")
+            .unwrap();
+        let mut fetch_config = FetchConfig::new();
+        fetch_config.set_scope(FetchScope::CurrentBranch);
+        let mut puller = test_puller();
+        puller.set_fetch_config(fetch_config);
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        let tracking = |name: &str| {
+            local
+                .refname_to_id(&format!("refs/remotes/origin/{name}"))
+                .unwrap()
+        };
+        assert_eq!(tracking("main"), new_commit);
+        assert_eq!(tracking("other"), old_commit.id());
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+    }
+
+    #[test]
+    fn test_pull_with_have_tips() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let last_synced = local.head().unwrap().target().unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut fetch_config = FetchConfig::new();
+        fetch_config.set_have_tips(vec![last_synced]);
+        let mut puller = test_puller();
+        puller.set_fetch_config(fetch_config);
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+        assert!(local.find_reference("refs/git-ops/have/0").is_err());
+    }
+
+    #[test]
+    fn test_pull_updates_remote_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let head_commit = upstream.head().unwrap().peel_to_commit().unwrap();
+        upstream.branch("develop", &head_commit, false).unwrap();
+        upstream.set_head("refs/heads/develop").unwrap();
+        let mut fetch_config = FetchConfig::new();
+        fetch_config.set_update_head(true);
+        let mut puller = test_puller();
+        puller.set_fetch_config(fetch_config);
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        let remote_head = local.find_reference("refs/remotes/origin/HEAD").unwrap();
+        assert_eq!(
+            remote_head.symbolic_target(),
+            Some("refs/remotes/origin/develop")
+        );
+        assert_eq!(local.head().unwrap().name(), Some("refs/heads/main"));
+    }
+
+    #[test]
+    fn test_reset_to_remote_discards_local_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let workdir = local.workdir().unwrap().to_path_buf();
+        commit_file(&local, "local.txt", "local\n", "Local only");
+        std::fs::write(workdir.join("README.md"), "dirty\n").unwrap();
+        let upstream_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        test_puller()
+            .reset_to_remote(&workdir, "main", true)
+            .unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(upstream_commit));
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("README.md")).unwrap(),
+            "initial\n"
+        );
+        assert!(!workdir.join("local.txt").exists());
+    }
+
+    #[test]
+    fn test_reset_to_remote_moves_branch_that_is_not_checked_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_head = local.head().unwrap().target();
+        let upstream_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let upstream_commit = upstream.find_commit(upstream_commit).unwrap();
+        upstream.branch("other", &upstream_commit, false).unwrap();
+
+        test_puller()
+            .reset_to_remote(local.workdir().unwrap(), "other", true)
+            .unwrap();
+        let missing = test_puller().reset_to_remote(local.workdir().unwrap(), "missing", false);
+
+        let other = local.find_reference("refs/heads/other").unwrap();
+        assert_eq!(other.target(), Some(upstream_commit.id()));
+        assert_eq!(local.head().unwrap().target(), local_head);
+        assert!(matches!(missing, Err(GitError::ResetFailed { .. })));
+    }
+
+    #[test]
+    fn test_pull_diverged_reports_oids() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local change");
+        let remote_commit = commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+
+        let result = test_puller().pull(local.workdir().unwrap());
+
+        match result {
+            Err(GitError::MergeRequired { local, remote, .. }) => {
+                assert_eq!(local, local_commit);
+                assert_eq!(remote, remote_commit);
+            }
+            other => panic!("expected MergeRequired, got {other:?}"),
+        }
+        let fetched = local.find_reference("refs/remotes/origin/main").unwrap();
+        assert_eq!(fetched.target(), Some(remote_commit));
+    }
+
+    #[test]
+    fn test_pull_with_merge_strategy_creates_merge_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local change");
+        let remote_commit = commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+
+        let outcome = test_puller()
+            .pull_with_strategy(local.workdir().unwrap(), MergeStrategy::Merge)
+            .unwrap();
+
+        let PullOutcome::Merged { commit } = outcome else {
+            panic!("expected a merge commit, got {outcome:?}");
+        };
+        let merge = local.find_commit(commit).unwrap();
+        assert_eq!(
+            merge.parent_ids().collect::<Vec<_>>(),
+            vec![local_commit, remote_commit]
+        );
+        assert_eq!(
+            merge.message(),
+            Some("Merge remote-tracking branch 'origin/main'")
+        );
+        assert_eq!(local.head().unwrap().target(), Some(commit));
+        assert!(local.workdir().unwrap().join("remote.txt").exists());
+    }
+
+    #[test]
+    fn test_pull_with_autostash_restores_local_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        commit_file(&local, "local.txt", "local\n", "Local change");
+        let readme = local.workdir().unwrap().join("README.md");
+        std::fs::write(&readme, "uncommitted\n").unwrap();
+        let mut puller = test_puller();
+        puller.set_merge_strategy(MergeStrategy::Merge);
+        puller.set_autostash(true);
+
+        let outcome = puller.pull_detailed(local.workdir().unwrap()).unwrap();
+
+        assert!(matches!(outcome, PullOutcome::Merged { .. }));
+        assert!(local.workdir().unwrap().join("remote.txt").exists());
+        assert_eq!(std::fs::read_to_string(&readme).unwrap(), "uncommitted\n");
+        let mut stashes = 0;
+        let mut local = Repository::open(local.workdir().unwrap()).unwrap();
+        local
+            .stash_foreach(|_, _, _| {
+                stashes += 1;
+                true
+            })
+            .unwrap();
+        assert_eq!(stashes, 0);
+    }
+
+    /// Make `repo` have no usable identity, whatever the global git config says
+    fn clear_identity(repo: &Repository) {
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "").unwrap();
+        config.set_str("user.email", "").unwrap();
+        assert!(repo.signature().is_err());
+    }
+
+    #[test]
+    fn test_pull_merge_without_identity_uses_fallback_signature() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        commit_file(&local, "local.txt", "local\n", "Local change");
+        clear_identity(&local);
+        let mut puller = test_puller();
+        puller.set_merge_strategy(MergeStrategy::Merge);
+
+        let outcome = puller.pull_detailed(local.workdir().unwrap()).unwrap();
+
+        let PullOutcome::Merged { commit } = outcome else {
+            panic!("expected a merge commit, got {outcome:?}");
+        };
+        let commit = local.find_commit(commit).unwrap();
+        assert_eq!(commit.committer().name(), Some("git-ops"));
+        assert_eq!(commit.author().email(), Some("git-ops@localhost"));
+    }
+
+    #[test]
+    fn test_pull_merge_with_configured_signature() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        commit_file(&local, "local.txt", "local\n", "Local change");
+        let mut puller = test_puller();
+        puller.set_merge_strategy(MergeStrategy::Merge);
+        puller.set_signature(Some(Author::now("Release Bot", "bot@example.com")));
+
+        let outcome = puller.pull_detailed(local.workdir().unwrap()).unwrap();
+
+        let PullOutcome::Merged { commit } = outcome else {
+            panic!("expected a merge commit, got {outcome:?}");
+        };
+        let commit = local.find_commit(commit).unwrap();
+        assert_eq!(commit.committer().name(), Some("Release Bot"));
+        assert_eq!(commit.author().email(), Some("bot@example.com"));
+    }
+
+    #[test]
+    fn test_pull_with_rebase_strategy_replays_local_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        commit_file(&local, "local.txt", "local\n", "Local change");
+        let remote_commit = commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+
+        let outcome = test_puller()
+            .pull_with_strategy(local.workdir().unwrap(), MergeStrategy::Rebase)
+            .unwrap();
+
+        let PullOutcome::Rebased { head } = outcome else {
+            panic!("expected a rebase, got {outcome:?}");
+        };
+        let rebased = local.find_commit(head).unwrap();
+        assert_eq!(rebased.message(), Some("Local change"));
+        assert_eq!(
+            rebased.parent_ids().collect::<Vec<_>>(),
+            vec![remote_commit]
+        );
+        assert_eq!(local.head().unwrap().name(), Some("refs/heads/main"));
+        assert_eq!(local.head().unwrap().target(), Some(head));
+        assert!(local.workdir().unwrap().join("remote.txt").exists());
+        assert!(local.workdir().unwrap().join("local.txt").exists());
+    }
+
+    #[test]
+    fn test_rebase_onto_replays_local_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let base = local.head().unwrap().target().unwrap();
+        commit_file(&local, "first.txt", "first\n", "First local");
+        commit_file(&local, "second.txt", "second\n", "Second local");
+        let remote_commit = commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        let puller = test_puller();
+        puller.fetch(local.workdir().unwrap()).unwrap();
+
+        let head = puller
+            .rebase_onto(local.workdir().unwrap(), "origin/main")
+            .unwrap();
+
+        let mut revwalk = local.revwalk().unwrap();
+        revwalk.push(head).unwrap();
+        let history: Vec<_> = revwalk
+            .map(|oid| {
+                let commit = local.find_commit(oid.unwrap()).unwrap();
+                (commit.id(), commit.summary().unwrap().to_string())
+            })
+            .collect();
+        assert_eq!(history[0].1, "Second local");
+        assert_eq!(history[1].1, "First local");
+        assert_eq!(history[2].0, remote_commit);
+        assert_eq!(history[3].0, base);
+        assert_eq!(history.len(), 4);
+        assert_eq!(local.head().unwrap().name(), Some("refs/heads/main"));
+        assert_eq!(local.head().unwrap().target(), Some(head));
+        assert!(local.workdir().unwrap().join("remote.txt").exists());
+    }
+
+    #[test]
+    fn test_rebase_onto_conflict_aborts() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "README.md", "local\n", "Local change");
+        commit_file(&upstream, "README.md", "remote\n", "Remote change");
+        let puller = test_puller();
+        puller.fetch(local.workdir().unwrap()).unwrap();
+
+        let result = puller.rebase_onto(local.workdir().unwrap(), "origin/main");
+
+        assert!(matches!(
+            result,
+            Err(GitError::MergeRequired { local, .. }) if local == local_commit
+        ));
+        assert_eq!(local.head().unwrap().name(), Some("refs/heads/main"));
+        assert_eq!(local.head().unwrap().target(), Some(local_commit));
+        assert_eq!(local.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_pull_with_strategy_conflict_changes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "README.md", "local\n", "Local change");
+        commit_file(&upstream, "README.md", "remote\n", "Remote change");
+        let workdir = local.workdir().unwrap().to_path_buf();
+
+        for strategy in [MergeStrategy::Merge, MergeStrategy::Rebase] {
+            let result = test_puller().pull_with_strategy(&workdir, strategy);
+
+            assert!(
+                matches!(result, Err(GitError::MergeRequired { .. })),
+                "{strategy:?} should require a manual merge"
+            );
+            assert_eq!(local.head().unwrap().target(), Some(local_commit));
+            assert_eq!(local.state(), git2::RepositoryState::Clean);
+            assert_eq!(
+                std::fs::read_to_string(workdir.join("README.md")).unwrap(),
+                "local\n"
+            );
+        }
+    }
+
+    #[test]
+    fn test_pull_with_rebase_strategy_aborts_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local change");
+        commit_file(&upstream, "remote.txt", "remote\n", "Remote change");
+        // Finishing copies notes to the rewritten commits, which fails while the notes ref is locked
+        let signature = local.signature().unwrap();
+        local
+            .note(
+                &signature,
+                &signature,
+                None,
+                local_commit,
+                "deployed",
+                false,
+            )
+            .unwrap();
+        let mut config = local.config().unwrap();
+        config
+            .set_str("notes.rewriteRef", "refs/notes/commits")
+            .unwrap();
+        std::fs::write(local.path().join("refs/notes/commits.lock"), "").unwrap();
+
+        let result =
+            test_puller().pull_with_strategy(local.workdir().unwrap(), MergeStrategy::Rebase);
+
+        assert!(matches!(result, Err(GitError::PullFailed { .. })));
+        assert_eq!(local.state(), git2::RepositoryState::Clean);
+        assert!(!local.path().join("rebase-merge").exists());
+        let head = local.head().unwrap();
+        assert_eq!(head.name(), Some("refs/heads/main"));
+        assert_eq!(head.target(), Some(local_commit));
+    }
+
+    #[test]
+    fn test_pull_if_behind_skips_fetch_when_remote_unchanged() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let head_commit = upstream.head().unwrap().peel_to_commit().unwrap();
+        upstream.branch("other", &head_commit, false).unwrap();
+
+        let outcome = test_puller()
+            .pull_if_behind(local.workdir().unwrap())
+            .unwrap();
+
+        assert_eq!(outcome, PullOutcome::UpToDate);
+        assert!(local.find_reference("refs/remotes/origin/other").is_err());
+    }
+
+    #[test]
+    fn test_pull_if_behind_fetches_when_remote_advanced() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let old_commit = local.head().unwrap().target().unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        let outcome = test_puller()
+            .pull_if_behind(local.workdir().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            PullOutcome::FastForwarded {
+                old: old_commit,
+                new: new_commit
+            }
+        );
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+    }
+
+    #[test]
+    fn test_can_fast_forward() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let workdir = local.workdir().unwrap().to_path_buf();
+        let puller = test_puller();
+        let up_to_date = puller.can_fast_forward(&workdir, "main").unwrap();
+        commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut remote = local.find_remote("origin").unwrap();
+        remote.fetch::<&str>(&[], None, None).unwrap();
+
+        let behind = puller.can_fast_forward(&workdir, "main").unwrap();
+        commit_file(&local, "local.txt", "local\n", "Local change");
+        let diverged = puller.can_fast_forward(&workdir, "main").unwrap();
+
+        assert!(!up_to_date);
+        assert!(behind);
+        assert!(!diverged);
+    }
+
+    #[test]
+    fn test_fetch_can_fast_forward_when_remote_advanced() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_head = local.head().unwrap().target();
+        let remote_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        let can_fast_forward = test_puller()
+            .fetch_can_fast_forward(local.workdir().unwrap())
+            .unwrap();
+
+        assert!(can_fast_forward);
+        assert_eq!(local.head().unwrap().target(), local_head);
+        assert_eq!(
+            local.refname_to_id("refs/remotes/origin/main").unwrap(),
+            remote_commit
+        );
+        assert!(!local.workdir().unwrap().join("file.txt").exists());
+    }
+
+    #[test]
+    fn test_fetch_can_fast_forward_when_diverged() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local change");
+        commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        let can_fast_forward = test_puller()
+            .fetch_can_fast_forward(local.workdir().unwrap())
+            .unwrap();
+
+        assert!(!can_fast_forward);
+        assert_eq!(local.head().unwrap().target(), Some(local_commit));
+    }
+
+    #[test]
+    fn test_fetch_and_checkout_pull_request_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let base = upstream.head().unwrap().peel_to_commit().unwrap();
+        let pr_head = commit_file(&upstream, "pr.txt", "pr\n", "PR change");
+        upstream
+            .reference("refs/pull/42/head", pr_head, false, "test")
+            .unwrap();
+        upstream
+            .reset(base.as_object(), git2::ResetType::Hard, None)
+            .unwrap();
+        let workdir = local.workdir().unwrap().to_path_buf();
+
+        let tip = test_puller()
+            .fetch_and_checkout(
+                &workdir,
+                "origin",
+                "+refs/pull/42/head:refs/remotes/origin/pr/42",
+                "pr-42",
+                false,
+            )
+            .unwrap();
+        let without_destination = test_puller()
+            .fetch_and_checkout(
+                &workdir,
+                "origin",
+                "refs/pull/42/head",
+                "refs/heads/pr-42-again",
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(tip, pr_head);
+        assert_eq!(without_destination, pr_head);
+        let head = local.head().unwrap();
+        assert_eq!(head.name(), Some("refs/heads/pr-42-again"));
+        assert_eq!(head.target(), Some(pr_head));
+        assert!(workdir.join("pr.txt").exists());
+    }
+
+    #[test]
+    fn test_fetch_and_checkout_keeps_local_modifications_unless_forced() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let pr_head = commit_file(&upstream, "README.md", "pr\n", "PR change");
+        let workdir = local.workdir().unwrap().to_path_buf();
+        let local_head = local.head().unwrap().target();
+        std::fs::write(workdir.join("README.md"), "local edit\n").unwrap();
+        let refspec = "+refs/heads/main:refs/remotes/origin/pr";
+
+        let safe = test_puller().fetch_and_checkout(&workdir, "origin", refspec, "pr", false);
+
+        assert!(matches!(safe, Err(GitError::CheckoutFailed { .. })));
+        assert!(local.find_reference("refs/heads/pr").is_err());
+        assert_eq!(local.head().unwrap().target(), local_head);
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("README.md")).unwrap(),
+            "local edit\n"
+        );
+
+        let forced = test_puller()
+            .fetch_and_checkout(&workdir, "origin", refspec, "pr", true)
+            .unwrap();
+
+        assert_eq!(forced, pr_head);
+        assert_eq!(local.head().unwrap().name(), Some("refs/heads/pr"));
+        assert_eq!(
+            std::fs::read_to_string(workdir.join("README.md")).unwrap(),
+            "pr\n"
+        );
+    }
+
+    #[test]
+    fn test_fetch_then_merge_fast_forward() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let old_head = local.head().unwrap().target().unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        let (summary, outcome) = test_puller()
+            .fetch_then_merge(local.workdir().unwrap(), "origin", "main")
+            .unwrap();
+
+        assert!(summary.received_objects > 0);
+        assert_eq!(
+            outcome,
+            MergeOutcome::FastForwarded {
+                from: old_head,
+                to: new_commit
+            }
+        );
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+        assert!(local.workdir().unwrap().join("file.txt").exists());
+    }
+
+    #[test]
+    fn test_fetch_then_merge_creates_merge_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "local.txt", "local\n", "Local");
+        let upstream_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+
+        let (_, outcome) = test_puller()
+            .fetch_then_merge(local.workdir().unwrap(), "origin", "main")
+            .unwrap();
+
+        let MergeOutcome::Merged { commit } = outcome else {
+            panic!("expected a merge commit, got {outcome:?}");
+        };
+        let merge = local.find_commit(commit).unwrap();
+        assert_eq!(
+            merge.parent_ids().collect::<Vec<_>>(),
+            vec![local_commit, upstream_commit]
+        );
+        assert_eq!(
+            merge.message(),
+            Some(
+                format!(
+                    "Merge branch 'main' of {}",
+                    upstream.path().parent().unwrap().display()
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(local.head().unwrap().target(), Some(commit));
+        assert!(local.workdir().unwrap().join("file.txt").exists());
+    }
+
+    #[test]
+    fn test_fetch_then_merge_uses_fetch_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        commit_file(&upstream, "file.txt", "update\n", "Update");
+        let mut fetch_config = FetchConfig::new();
+        fetch_config.set_update_head(true);
+        let mut puller = test_puller();
+        puller.set_fetch_config(fetch_config);
+
+        puller
+            .fetch_then_merge(local.workdir().unwrap(), "origin", "main")
+            .unwrap();
+
+        let remote_head = local.find_reference("refs/remotes/origin/HEAD").unwrap();
+        assert_eq!(
+            remote_head.symbolic_target(),
+            Some("refs/remotes/origin/main")
+        );
+    }
+
+    #[test]
+    fn test_fetch_then_merge_conflict_changes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let local_commit = commit_file(&local, "README.md", "local\n", "Local");
+        commit_file(&upstream, "README.md", "upstream\n", "Update");
+
+        let result = test_puller().fetch_then_merge(local.workdir().unwrap(), "origin", "main");
+
+        assert!(matches!(result, Err(GitError::MergeRequired { .. })));
+        assert_eq!(local.head().unwrap().target(), Some(local_commit));
+        assert_eq!(
+            std::fs::read_to_string(local.workdir().unwrap().join("README.md")).unwrap(),
+            "local\n"
+        );
+    }
+
+    #[test]
+    fn test_pull_with_depth_keeps_repository_shallow() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream = init_repo(&temp_dir.path().join("upstream"));
+        let first = commit_file(&upstream, "file.txt", "one\n", "First");
+        commit_file(&upstream, "file.txt", "two\n", "Second");
+        let local = shallow_clone(temp_dir.path());
+        let server = GitHttpServer::serve(temp_dir.path());
+        local
+            .remote_set_url("origin", &server.url("upstream"))
+            .unwrap();
+        let new_commit = commit_file(&upstream, "file.txt", "three\n", "Third");
+        let mut fetch_config = FetchConfig::default();
+        fetch_config.set_depth(Some(1));
+        let mut puller = test_puller();
+        puller.set_fetch_config(fetch_config);
+
+        puller.pull(local.workdir().unwrap()).unwrap();
+
+        assert_eq!(local.head().unwrap().target(), Some(new_commit));
+        assert!(local.is_shallow());
+        assert!(local.find_commit(first).is_err());
+    }
+
+    #[test]
+    fn test_unshallow_fetches_full_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream = init_repo(&temp_dir.path().join("upstream"));
+        let first = commit_file(&upstream, "file.txt", "one\n", "First");
+        commit_file(&upstream, "file.txt", "two\n", "Second");
+        let local = shallow_clone(temp_dir.path());
+
+        test_puller()
+            .unshallow(local.workdir().unwrap(), "origin")
+            .unwrap();
+
+        assert!(!local.is_shallow());
+        assert!(local.find_commit(first).is_ok());
+    }
+
+    #[test]
+    fn test_fetch_all_remotes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, local) = upstream_and_clone(temp_dir.path());
+        let origin_commit = commit_file(&upstream, "file.txt", "update\n", "Update");
+        let backup_path = temp_dir.path().join("backup");
+        let backup = init_repo(&backup_path);
+        let backup_commit = commit_file(&backup, "backup.txt", "backup\n", "Backup");
+        local
+            .remote("backup", backup_path.to_str().unwrap())
+            .unwrap();
+
+        let summaries = test_puller()
+            .fetch_all_remotes(local.workdir().unwrap())
+            .unwrap();
+
+        let names: Vec<&str> = summaries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["backup", "origin"]);
+        assert!(summaries
+            .iter()
+            .all(|(_, summary)| summary.received_objects > 0));
+        assert_eq!(
+            local.refname_to_id("refs/remotes/origin/main").unwrap(),
+            origin_commit
+        );
+        assert_eq!(
+            local.refname_to_id("refs/remotes/backup/main").unwrap(),
+            backup_commit
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use git2::Repository;
+use std::cell::RefCell;
+use std::path::Path;
+
+use crate::auth::{self, SshConfig, TokenAuth};
+use crate::error::GitError;
+use crate::remote;
+
+/// Push operations for Git repositories
+#[derive(Clone)]
+pub struct GitPusher {
+    ssh_config: SshConfig,
+    /// Token offered to HTTPS remotes before the credential helper and environment
+    https_token: Option<String>,
+    /// Environment variables tried for HTTPS tokens after the credential helper
+    token_auth: TokenAuth,
+    /// Remote that refs are pushed to
+    remote_name: String,
+    /// Proxy for HTTP(S) remotes, `None` to detect it from git config and the environment
+    proxy: Option<String>,
+}
+
+impl GitPusher {
+    /// Create a new GitPusher with the provided SSH configuration
+    pub fn new(ssh_config: SshConfig) -> Self {
+        Self {
+            ssh_config,
+            https_token: None,
+            token_auth: TokenAuth::default(),
+            proxy: None,
+            remote_name: "origin".to_string(),
+        }
+    }
+
+    /// Set the remote that refs are pushed to; defaults to `origin`
+    pub fn set_remote_name(&mut self, remote_name: String) {
+        self.remote_name = remote_name;
+    }
+
+    /// Offer `https_token` to HTTPS remotes before the credential helper and environment tokens
+    pub fn set_https_token(&mut self, https_token: Option<String>) {
+        self.https_token = https_token;
+    }
+
+    /// Set the environment variables tried for HTTPS tokens; defaults to GitHub's
+    pub fn set_token_auth(&mut self, token_auth: TokenAuth) {
+        self.token_auth = token_auth;
+    }
+
+    /// Connect to HTTP(S) remotes through `proxy`; `None` detects it from git config and the environment
+    pub fn set_proxy(&mut self, proxy: Option<String>) {
+        self.proxy = proxy;
+    }
+
+    /// Push `refspecs` to the configured remote
+    ///
+    /// Packs pushed over HTTPS are streamed with chunked transfer encoding, so large pushes
+    /// don't need git's `http.postBuffer` raised; libgit2 doesn't read that setting.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `refspecs` - Refspecs to push, e.g. `refs/heads/main:refs/heads/main`; prefix with `+` to force
+    ///
+    /// # Errors
+    /// Returns `PushFailed` if the push fails or the remote rejects any ref, e.g. a
+    /// non-fast-forward update
+    pub fn push(&self, repo_path: &Path, refspecs: &[&str]) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut remote = repo
+            .find_remote(&self.remote_name)
+            .map_err(|e| GitError::PushFailed {
+                path: repo_path.to_path_buf(),
+                url: None,
+                source: e,
+            })?;
+        let remote_url = remote.url().unwrap_or("").to_string();
+        let push_error = |e: git2::Error| GitError::PushFailed {
+            path: repo_path.to_path_buf(),
+            url: Some(remote::strip_url_credentials(&remote_url)),
+            source: e,
+        };
+
+        let rejected = RefCell::new(Vec::new());
+        let mut callbacks = auth::remote_callbacks(
+            &self.ssh_config,
+            &remote_url,
+            self.https_token.as_deref(),
+            &self.token_auth,
+        )?;
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(status) = status {
+                rejected.borrow_mut().push(format!("{refname} ({status})"));
+            }
+            Ok(())
+        });
+
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        push_options.proxy_options(remote::proxy_options(self.proxy.as_deref()));
+        remote
+            .push(refspecs, Some(&mut push_options))
+            .map_err(push_error)?;
+        drop(push_options);
+
+        let rejected = rejected.into_inner();
+        if !rejected.is_empty() {
+            return Err(push_error(git2::Error::from_str(&format!(
+                "Remote rejected {}",
+                rejected.join(", ")
+            ))));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use git2::build::RepoBuilder;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn test_pusher() -> GitPusher {
+        GitPusher::new(SshConfig::new(
+            vec![],
+            PathBuf::from("/nonexistent/known_hosts"),
+            false,
+        ))
+    }
+
+    /// A bare `origin` and a clone of it, both at one commit on `main`
+    fn bare_remote_and_clone(temp_dir: &TempDir) -> (Repository, Repository) {
+        let seed_path = temp_dir.path().join("seed");
+        let seed = init_repo(&seed_path);
+        commit_file(&seed, "README.md", "initial\n", "Initial commit");
+        let bare = RepoBuilder::new()
+            .bare(true)
+            .clone(
+                seed_path.to_str().unwrap(),
+                &temp_dir.path().join("origin.git"),
+            )
+            .unwrap();
+        let local = Repository::clone(bare.path().to_str().unwrap(), temp_dir.path().join("local"))
+            .unwrap();
+        let mut config = local.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        (bare, local)
+    }
+
+    #[test]
+    fn test_push_moves_remote_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let (bare, local) = bare_remote_and_clone(&temp_dir);
+        let commit = commit_file(&local, "file.txt", "update\n", "Update");
+
+        test_pusher()
+            .push(
+                local.workdir().unwrap(),
+                &["refs/heads/main:refs/heads/main"],
+            )
+            .unwrap();
+
+        assert_eq!(bare.refname_to_id("refs/heads/main").unwrap(), commit);
+    }
+
+    #[test]
+    fn test_push_non_fast_forward_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let (bare, local) = bare_remote_and_clone(&temp_dir);
+        let remote_head = bare.refname_to_id("refs/heads/main").unwrap();
+        let parentless = {
+            let tree = local.head().unwrap().peel_to_tree().unwrap();
+            let signature = local.signature().unwrap();
+            local
+                .commit(None, &signature, &signature, "Rewrite", &tree, &[])
+                .unwrap()
+        };
+        local
+            .reference("refs/heads/rewrite", parentless, false, "test")
+            .unwrap();
+
+        let result = test_pusher().push(
+            local.workdir().unwrap(),
+            &["refs/heads/rewrite:refs/heads/main"],
+        );
+
+        assert!(matches!(result, Err(GitError::PushFailed { .. })));
+        assert_eq!(bare.refname_to_id("refs/heads/main").unwrap(), remote_head);
+    }
+}
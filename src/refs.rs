@@ -0,0 +1,137 @@
+use git2::{ErrorCode, Oid, Repository};
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// Longest chain of symbolic refs followed before giving up, matching git
+const MAX_SYMREF_DEPTH: usize = 5;
+
+/// Low-level ref manipulation for Git repositories
+pub struct GitRefs;
+
+impl GitRefs {
+    /// Create a direct ref pointing at `oid`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Full ref name, e.g. `refs/heads/feature` or `refs/tags/v1.0`
+    /// * `oid` - Object the ref points at
+    /// * `force` - Overwrite the ref if it already exists
+    pub fn create_ref(repo_path: &Path, name: &str, oid: Oid, force: bool) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        repo.reference(name, oid, force, "git-ops: create ref")?;
+        Ok(())
+    }
+
+    /// Follow a symbolic ref (e.g. `HEAD`) to the name of the concrete ref it points at
+    ///
+    /// A direct ref resolves to itself. The target doesn't need to exist, so `HEAD` on an
+    /// unborn branch resolves to that branch's name.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Ref name to resolve, e.g. `HEAD` or `refs/remotes/origin/HEAD`
+    pub fn resolve_symbolic_ref(repo_path: &Path, name: &str) -> Result<String, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut current = name.to_string();
+        for _ in 0..=MAX_SYMREF_DEPTH {
+            let reference = match repo.find_reference(&current) {
+                Ok(reference) => reference,
+                Err(e) if e.code() == ErrorCode::NotFound && current != name => return Ok(current),
+                Err(e) => return Err(GitError::Git(e)),
+            };
+
+            match reference.symbolic_target() {
+                Some(target) => current = target.to_string(),
+                None => return Ok(current),
+            }
+        }
+
+        Err(GitError::Git(git2::Error::from_str(&format!(
+            "Symbolic ref {name} is nested more than {MAX_SYMREF_DEPTH} levels deep"
+        ))))
+    }
+
+    /// Delete a ref
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Full ref name, e.g. `refs/heads/feature`
+    pub fn delete_ref(repo_path: &Path, name: &str) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        repo.find_reference(name)?.delete()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_and_delete_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        let second = commit_file(&repo, "file.txt", "two\n", "Second");
+
+        GitRefs::create_ref(temp_dir.path(), "refs/heads/fixture", first, false).unwrap();
+        let duplicate = GitRefs::create_ref(temp_dir.path(), "refs/heads/fixture", second, false);
+        GitRefs::create_ref(temp_dir.path(), "refs/heads/fixture", second, true).unwrap();
+        let moved = repo.refname_to_id("refs/heads/fixture").unwrap();
+        GitRefs::delete_ref(temp_dir.path(), "refs/heads/fixture").unwrap();
+
+        assert!(matches!(duplicate, Err(GitError::Git(_))));
+        assert_eq!(moved, second);
+        assert!(repo.find_reference("refs/heads/fixture").is_err());
+    }
+
+    #[test]
+    fn test_delete_missing_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        init_repo(temp_dir.path());
+
+        let result = GitRefs::delete_ref(temp_dir.path(), "refs/heads/missing");
+
+        assert!(matches!(result, Err(GitError::Git(_))));
+    }
+
+    #[test]
+    fn test_resolve_symbolic_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let unborn = GitRefs::resolve_symbolic_ref(temp_dir.path(), "HEAD").unwrap();
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        repo.reference("refs/remotes/origin/main", commit, false, "test")
+            .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main",
+            false,
+            "test",
+        )
+        .unwrap();
+
+        let remote_head =
+            GitRefs::resolve_symbolic_ref(temp_dir.path(), "refs/remotes/origin/HEAD").unwrap();
+        let direct = GitRefs::resolve_symbolic_ref(temp_dir.path(), "refs/heads/main").unwrap();
+
+        assert_eq!(unborn, "refs/heads/main");
+        assert_eq!(remote_head, "refs/remotes/origin/main");
+        assert_eq!(direct, "refs/heads/main");
+    }
+}
@@ -0,0 +1,502 @@
+use git2::{ErrorClass, ErrorCode, Oid, Repository, RepositoryState};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::command;
+use crate::error::GitError;
+
+/// Progress of a merge or rebase that stopped before completing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpProgress {
+    /// A merge is in progress, merging the given commits into HEAD
+    Merge { merge_heads: Vec<Oid> },
+    /// A rebase is in progress
+    ///
+    /// `step` is the 1-based operation currently being applied (0 if none has been applied yet)
+    Rebase { step: usize, total: usize },
+}
+
+/// Facts about the repository a path opens to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoInfo {
+    /// Path to the `.git` directory (or the repository itself when bare)
+    pub git_dir: PathBuf,
+    /// Root of the working tree, `None` for bare repositories
+    pub workdir: Option<PathBuf>,
+    /// Whether the repository is bare
+    pub is_bare: bool,
+    /// Working tree of the parent repository when this repository is one of its submodules
+    pub superproject: Option<PathBuf>,
+}
+
+impl RepoInfo {
+    /// Check if the repository is a submodule of another repository
+    pub fn is_submodule(&self) -> bool {
+        self.superproject.is_some()
+    }
+}
+
+/// Repository-level inspection operations
+pub struct GitRepository;
+
+impl GitRepository {
+    /// Report how far along an in-progress merge or rebase is
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `None` when no merge or rebase is in progress
+    pub fn operation_progress(repo_path: &Path) -> Result<Option<OpProgress>, GitError> {
+        let mut repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        match repo.state() {
+            RepositoryState::Merge => {
+                let mut merge_heads = Vec::new();
+                repo.mergehead_foreach(|oid| {
+                    merge_heads.push(*oid);
+                    true
+                })?;
+
+                Ok(Some(OpProgress::Merge { merge_heads }))
+            }
+            // The apply backend keeps its state in `rebase-apply`, which libgit2 can't open
+            RepositoryState::Rebase | RepositoryState::ApplyMailboxOrRebase => {
+                Self::apply_progress(&repo).map(Some)
+            }
+            RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+                let mut rebase = repo.open_rebase(None)?;
+                let step = rebase.operation_current().map_or(0, |index| index + 1);
+
+                Ok(Some(OpProgress::Rebase {
+                    step,
+                    total: rebase.len(),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Read a `git rebase --apply` rebase's progress from `rebase-apply/next` and `rebase-apply/last`
+    fn apply_progress(repo: &Repository) -> Result<OpProgress, GitError> {
+        let read_number = |name: &str| {
+            let path = repo.path().join("rebase-apply").join(name);
+            let contents = std::fs::read_to_string(&path).map_err(|e| GitError::Io {
+                path: path.clone(),
+                source: e,
+            })?;
+            contents.trim().parse::<usize>().map_err(|_| {
+                GitError::Git(git2::Error::from_str(&format!(
+                    "Invalid rebase progress in {}",
+                    path.display()
+                )))
+            })
+        };
+
+        Ok(OpProgress::Rebase {
+            step: read_number("next")?,
+            total: read_number("last")?,
+        })
+    }
+
+    /// Describe the repository at `repo_path`, including whether it is a submodule
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn repo_info(repo_path: &Path) -> Result<RepoInfo, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let workdir = repo.workdir().map(Path::to_path_buf);
+        let superproject = match &workdir {
+            Some(workdir) => Self::find_superproject(workdir),
+            None => None,
+        };
+
+        Ok(RepoInfo {
+            git_dir: repo.path().to_path_buf(),
+            workdir,
+            is_bare: repo.is_bare(),
+            superproject,
+        })
+    }
+
+    /// Find the root of the repository containing `path`, searching parent directories like `git`
+    ///
+    /// # Arguments
+    /// * `path` - Any path inside a working tree or git directory
+    ///
+    /// # Returns
+    /// The working tree root, or the repository directory itself for bare repositories
+    pub fn discover_root(path: &Path) -> Result<PathBuf, GitError> {
+        let repo = Repository::discover(path).map_err(|e| GitError::OpenFailed {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let root = repo.workdir().unwrap_or_else(|| repo.path());
+        Ok(root.to_path_buf())
+    }
+
+    /// Get when the repository last fetched, from the modification time of `FETCH_HEAD`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// `None` if the repository has never fetched
+    pub fn last_fetch_time(repo_path: &Path) -> Result<Option<SystemTime>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let fetch_head = repo.path().join("FETCH_HEAD");
+        let io_error = |e: std::io::Error| GitError::Io {
+            path: fetch_head.clone(),
+            source: e,
+        };
+
+        match std::fs::metadata(&fetch_head) {
+            Ok(metadata) => metadata.modified().map(Some).map_err(io_error),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    /// Write the commit-graph file for all commits reachable from refs
+    ///
+    /// The commit-graph caches commit parents and generation numbers, which keeps
+    /// ancestry, ahead/behind and merge-base queries fast on large repositories. libgit2
+    /// can't write it, so this runs `git commit-graph write` and requires the `git` command line tool.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn write_commit_graph(repo_path: &Path) -> Result<(), GitError> {
+        command::run_git_checked(repo_path, &["commit-graph", "write", "--reachable"])?;
+        Ok(())
+    }
+
+    /// Read the repository's `description` file, as shown by gitweb and cgit
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// The description with surrounding whitespace trimmed, `None` if the file doesn't exist
+    pub fn description(repo_path: &Path) -> Result<Option<String>, GitError> {
+        let description_path = Self::description_path(repo_path)?;
+
+        match std::fs::read_to_string(&description_path) {
+            Ok(description) => Ok(Some(description.trim().to_string())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(GitError::Io {
+                path: description_path,
+                source: e,
+            }),
+        }
+    }
+
+    /// Replace the repository's `description` file
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `description` - New description, put on one line by collapsing line breaks to spaces
+    pub fn set_description(repo_path: &Path, description: &str) -> Result<(), GitError> {
+        let description_path = Self::description_path(repo_path)?;
+
+        let description = description
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        std::fs::write(&description_path, format!("{description}\n")).map_err(|e| GitError::Io {
+            path: description_path,
+            source: e,
+        })
+    }
+
+    /// Path of the `description` file, inside `.git` or at the root of a bare repository
+    fn description_path(repo_path: &Path) -> Result<PathBuf, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        Ok(repo.path().join("description"))
+    }
+
+    /// Find the repository that registers `workdir` as one of its submodules
+    fn find_superproject(workdir: &Path) -> Option<PathBuf> {
+        let workdir = workdir.canonicalize().ok()?;
+        let parent = Repository::discover(workdir.parent()?).ok()?;
+        let parent_workdir = parent.workdir()?.canonicalize().ok()?;
+
+        let is_registered = parent.submodules().ok()?.iter().any(|submodule| {
+            parent_workdir
+                .join(submodule.path())
+                .canonicalize()
+                .map(|path| path == workdir)
+                .unwrap_or(false)
+        });
+
+        is_registered.then_some(parent_workdir)
+    }
+}
+
+/// Report `error` as `ShallowBoundary` when `repo` is shallow and `error` comes from missing history
+///
+/// Walking past a shallow repository's boundary surfaces as missing objects, a missing merge
+/// base or nothing to describe. Other errors, and any error in a complete repository, are
+/// passed to `fallback`.
+pub(crate) fn shallow_boundary_error(
+    repo: &Repository,
+    repo_path: &Path,
+    operation: &str,
+    error: git2::Error,
+    fallback: impl FnOnce(git2::Error) -> GitError,
+) -> GitError {
+    let missing_history = match error.class() {
+        ErrorClass::Odb | ErrorClass::Describe => true,
+        ErrorClass::Merge => error.code() == ErrorCode::NotFound,
+        _ => false,
+    };
+
+    if missing_history && repo.is_shallow() {
+        GitError::ShallowBoundary {
+            operation: operation.to_string(),
+            path: repo_path.to_path_buf(),
+            source: error,
+        }
+    } else {
+        fallback(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repo_info_plain_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let info = GitRepository::repo_info(temp_dir.path()).unwrap();
+
+        assert!(!info.is_bare);
+        assert!(!info.is_submodule());
+        assert_eq!(
+            info.workdir.map(|path| path.canonicalize().unwrap()),
+            Some(temp_dir.path().canonicalize().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_repo_info_submodule() {
+        let temp_dir = TempDir::new().unwrap();
+        let child = init_repo(&temp_dir.path().join("child"));
+        commit_file(&child, "lib.txt", "lib\n", "Child commit");
+        let parent_path = temp_dir.path().join("parent");
+        let parent = init_repo(&parent_path);
+        commit_file(&parent, "app.txt", "app\n", "Parent commit");
+        let child_url = temp_dir.path().join("child");
+        let mut submodule = parent
+            .submodule(child_url.to_str().unwrap(), Path::new("sub"), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+
+        let info = GitRepository::repo_info(&parent_path.join("sub")).unwrap();
+
+        assert!(info.is_submodule());
+        assert_eq!(info.superproject, Some(parent_path.canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn test_operation_progress_clean_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let progress = GitRepository::operation_progress(temp_dir.path()).unwrap();
+
+        assert_eq!(progress, None);
+    }
+
+    #[test]
+    fn test_operation_progress_merge_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let base = commit_file(&repo, "file.txt", "base\n", "Base");
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("feature", &base_commit, false).unwrap();
+        commit_file(&repo, "file.txt", "main\n", "Main change");
+
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+            .unwrap();
+        let feature = commit_file(&repo, "file.txt", "feature\n", "Feature change");
+
+        repo.set_head("refs/heads/main").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+            .unwrap();
+        let annotated = repo.find_annotated_commit(feature).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+
+        let progress = GitRepository::operation_progress(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            progress,
+            Some(OpProgress::Merge {
+                merge_heads: vec![feature]
+            })
+        );
+    }
+
+    #[test]
+    fn test_operation_progress_rebase() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let base = commit_file(&repo, "base.txt", "base\n", "Base");
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("upstream", &base_commit, false).unwrap();
+        commit_file(&repo, "one.txt", "one\n", "One");
+        commit_file(&repo, "two.txt", "two\n", "Two");
+
+        let upstream_ref = repo.find_reference("refs/heads/upstream").unwrap();
+        let upstream = repo.reference_to_annotated_commit(&upstream_ref).unwrap();
+        let mut rebase = repo.rebase(None, Some(&upstream), None, None).unwrap();
+        rebase.next().unwrap().unwrap();
+
+        let progress = GitRepository::operation_progress(temp_dir.path()).unwrap();
+
+        assert_eq!(progress, Some(OpProgress::Rebase { step: 1, total: 2 }));
+    }
+
+    #[test]
+    fn test_operation_progress_apply_rebase() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let base = commit_file(&repo, "file.txt", "base\n", "Base");
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.branch("feature", &base_commit, false).unwrap();
+        commit_file(&repo, "file.txt", "main\n", "Main change");
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+            .unwrap();
+        commit_file(&repo, "other.txt", "other\n", "Other change");
+        commit_file(&repo, "file.txt", "feature\n", "Feature change");
+        let output = command::run_git(temp_dir.path(), &["rebase", "--apply", "main"]).unwrap();
+        assert!(
+            !output.status.success(),
+            "rebase should stop on the conflict"
+        );
+
+        let progress = GitRepository::operation_progress(temp_dir.path()).unwrap();
+
+        assert_eq!(progress, Some(OpProgress::Rebase { step: 2, total: 2 }));
+    }
+
+    #[test]
+    fn test_last_fetch_time() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let never_fetched = GitRepository::last_fetch_time(temp_dir.path()).unwrap();
+        std::fs::write(repo.path().join("FETCH_HEAD"), "").unwrap();
+
+        let fetched = GitRepository::last_fetch_time(temp_dir.path()).unwrap();
+
+        assert_eq!(never_fetched, None);
+        assert!(fetched.is_some());
+    }
+
+    #[test]
+    fn test_write_commit_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        GitRepository::write_commit_graph(temp_dir.path()).unwrap();
+
+        assert!(repo.path().join("objects/info/commit-graph").exists());
+    }
+
+    #[test]
+    fn test_set_description_bare_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init_bare(temp_dir.path()).unwrap();
+
+        GitRepository::set_description(temp_dir.path(), "Mirror of upstream").unwrap();
+        let description = GitRepository::description(temp_dir.path()).unwrap();
+
+        assert_eq!(description.as_deref(), Some("Mirror of upstream"));
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("description")).unwrap(),
+            "Mirror of upstream\n"
+        );
+    }
+
+    #[test]
+    fn test_set_description_collapses_line_breaks() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+
+        GitRepository::set_description(temp_dir.path(), "Mirror of\r\nupstream\n\n  (read-only)\n")
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("description")).unwrap(),
+            "Mirror of upstream (read-only)\n"
+        );
+    }
+
+    #[test]
+    fn test_description_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let _ = std::fs::remove_file(repo.path().join("description"));
+
+        let description = GitRepository::description(temp_dir.path()).unwrap();
+
+        assert_eq!(description, None);
+    }
+
+    #[test]
+    fn test_discover_root_from_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        init_repo(temp_dir.path());
+        let nested = temp_dir.path().join("src/nested");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let root = GitRepository::discover_root(&nested).unwrap();
+
+        assert_eq!(
+            root.canonicalize().unwrap(),
+            temp_dir.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_discover_root_bare_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init_bare(temp_dir.path()).unwrap();
+
+        let root = GitRepository::discover_root(&temp_dir.path().join("refs")).unwrap();
+
+        assert_eq!(
+            root.canonicalize().unwrap(),
+            temp_dir.path().canonicalize().unwrap()
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use git2::{Repository, ResetType};
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// What `reset` updates besides moving the current branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    /// Only move the branch; the index and working tree keep their changes, now staged
+    Soft,
+    /// Move the branch and reset the index; changes stay in the working tree, unstaged
+    Mixed,
+    /// Move the branch and reset the index and working tree, discarding local changes
+    Hard,
+}
+
+impl From<ResetMode> for ResetType {
+    fn from(mode: ResetMode) -> Self {
+        match mode {
+            ResetMode::Soft => ResetType::Soft,
+            ResetMode::Mixed => ResetType::Mixed,
+            ResetMode::Hard => ResetType::Hard,
+        }
+    }
+}
+
+/// Reset operations for Git repositories
+pub struct GitReset;
+
+impl GitReset {
+    /// Move the current branch to `rev`, like `git reset --soft`, `--mixed` or `--hard`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `rev` - Revision to reset to (branch, tag, SHA, `HEAD~1`, ...)
+    /// * `mode` - Whether the index and working tree are reset too
+    /// * `confirm_hard` - Must be set for `ResetMode::Hard`, which discards local changes
+    ///
+    /// # Errors
+    /// Returns `HardResetNotConfirmed` for an unconfirmed hard reset, `RevisionNotFound` if
+    /// `rev` doesn't resolve to a commit, and `ResetFailed` if the reset itself fails
+    pub fn reset(
+        repo_path: &Path,
+        rev: &str,
+        mode: ResetMode,
+        confirm_hard: bool,
+    ) -> Result<(), GitError> {
+        if mode == ResetMode::Hard && !confirm_hard {
+            return Err(GitError::HardResetNotConfirmed {
+                path: repo_path.to_path_buf(),
+            });
+        }
+
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| GitError::RevisionNotFound {
+                rev: rev.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            })?;
+
+        repo.reset(commit.as_object(), mode.into(), None)
+            .map_err(|e| GitError::ResetFailed {
+                target: rev.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use git2::{Oid, Status};
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Two commits of `file.txt`, plus an uncommitted edit to it; returns the first commit
+    fn repo_with_history(temp_dir: &TempDir) -> (Repository, Oid) {
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        commit_file(&repo, "file.txt", "two\n", "Second");
+        fs::write(temp_dir.path().join("file.txt"), "local\n").unwrap();
+        (repo, first)
+    }
+
+    #[test]
+    fn test_soft_reset_keeps_index_and_working_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let (repo, first) = repo_with_history(&temp_dir);
+
+        GitReset::reset(temp_dir.path(), "HEAD~1", ResetMode::Soft, false).unwrap();
+
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        let status = repo.status_file(Path::new("file.txt")).unwrap();
+        assert!(status.contains(Status::INDEX_MODIFIED | Status::WT_MODIFIED));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "local\n"
+        );
+    }
+
+    #[test]
+    fn test_mixed_reset_unstages_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (repo, first) = repo_with_history(&temp_dir);
+
+        GitReset::reset(temp_dir.path(), "HEAD~1", ResetMode::Mixed, false).unwrap();
+
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        let status = repo.status_file(Path::new("file.txt")).unwrap();
+        assert_eq!(status, Status::WT_MODIFIED);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "local\n"
+        );
+    }
+
+    #[test]
+    fn test_hard_reset_discards_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let (repo, first) = repo_with_history(&temp_dir);
+
+        GitReset::reset(temp_dir.path(), "HEAD~1", ResetMode::Hard, true).unwrap();
+
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        assert_eq!(
+            repo.status_file(Path::new("file.txt")).unwrap(),
+            Status::CURRENT
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "one\n"
+        );
+    }
+
+    #[test]
+    fn test_hard_reset_needs_confirmation() {
+        let temp_dir = TempDir::new().unwrap();
+        let (repo, _first) = repo_with_history(&temp_dir);
+        let head = repo.head().unwrap().target();
+
+        let result = GitReset::reset(temp_dir.path(), "HEAD~1", ResetMode::Hard, false);
+
+        assert!(matches!(
+            result,
+            Err(GitError::HardResetNotConfirmed { .. })
+        ));
+        assert_eq!(repo.head().unwrap().target(), head);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("file.txt")).unwrap(),
+            "local\n"
+        );
+    }
+}
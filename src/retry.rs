@@ -0,0 +1,191 @@
+use git2::{ErrorClass, ErrorCode};
+use std::thread;
+use std::time::Duration;
+
+/// How often to retry a fetch that fails with a transient network error
+///
+/// Only errors libgit2 classifies as network or HTTP failures (DNS lookups, refused or
+/// dropped connections, 5xx responses) are retried. Authentication, certificate, merge and
+/// other errors fail on the first attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// A single attempt, without retries
+    fn default() -> Self {
+        Self::new(1, Duration::ZERO)
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy making up to `max_attempts` attempts in total
+    ///
+    /// The wait before each retry doubles, starting at `base_delay`. A `max_attempts` of 0
+    /// is treated as 1, i.e. no retries.
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+        }
+    }
+
+    /// Get the maximum number of attempts, including the first
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Get the wait before the first retry
+    pub fn base_delay(&self) -> Duration {
+        self.base_delay
+    }
+
+    /// Run `operation` until it succeeds, fails with a non-transient error, or attempts run out
+    pub(crate) fn run<T>(
+        &self,
+        mut operation: impl FnMut() -> Result<T, git2::Error>,
+    ) -> Result<T, git2::Error> {
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Err(e) if attempt < self.max_attempts && is_transient(&e) => {
+                    thread::sleep(self.delay_before_retry(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Wait after failed attempt number `attempt` (starting at 1)
+    fn delay_before_retry(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+/// Whether `error` is a network failure that may succeed if tried again
+///
+/// Rejected credentials and certificates fail the same way every time, even when libgit2
+/// reports them in the HTTP class, as do 4xx responses other than timeouts and rate limits.
+fn is_transient(error: &git2::Error) -> bool {
+    if matches!(error.code(), ErrorCode::Auth | ErrorCode::Certificate)
+        || error.message().contains("authentication replays")
+    {
+        return false;
+    }
+
+    let client_error = error
+        .message()
+        .strip_prefix("unexpected http status code: ")
+        .is_some_and(|status| status.starts_with('4') && !matches!(status, "408" | "429"));
+    matches!(error.class(), ErrorClass::Net | ErrorClass::Http) && !client_error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(class: ErrorClass) -> git2::Error {
+        git2::Error::new(ErrorCode::GenericError, class, "failed")
+    }
+
+    #[test]
+    fn test_retries_transient_errors_until_success() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(1));
+        let mut attempts = 0;
+
+        let result = policy.run(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(error(ErrorClass::Net))
+            } else {
+                Ok(attempts)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let policy = RetryPolicy::new(2, Duration::from_millis(1));
+        let mut attempts = 0;
+
+        let result: Result<(), _> = policy.run(|| {
+            attempts += 1;
+            Err(error(ErrorClass::Http))
+        });
+
+        assert_eq!(result.unwrap_err().class(), ErrorClass::Http);
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn test_does_not_retry_other_errors() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(1));
+        let mut attempts = 0;
+
+        let result: Result<(), _> = policy.run(|| {
+            attempts += 1;
+            Err(error(ErrorClass::Ssh))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_does_not_retry_http_auth_errors() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(1));
+        let failures = [
+            git2::Error::new(ErrorCode::Auth, ErrorClass::Http, "authentication failed"),
+            git2::Error::new(
+                ErrorCode::GenericError,
+                ErrorClass::Http,
+                "too many redirects or authentication replays",
+            ),
+            git2::Error::new(
+                ErrorCode::GenericError,
+                ErrorClass::Http,
+                "unexpected http status code: 404",
+            ),
+        ];
+
+        for failure in failures {
+            let mut attempts = 0;
+            let result: Result<(), _> = policy.run(|| {
+                attempts += 1;
+                Err(git2::Error::new(
+                    failure.code(),
+                    failure.class(),
+                    failure.message(),
+                ))
+            });
+
+            assert!(result.is_err());
+            assert_eq!(attempts, 1, "retried {}", failure.message());
+        }
+    }
+
+    #[test]
+    fn test_delay_doubles_between_retries() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(100));
+
+        let delays: Vec<_> = (1..4)
+            .map(|attempt| policy.delay_before_retry(attempt))
+            .collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(400),
+            ]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use git2::{DescribeFormatOptions, ErrorCode, Oid, Repository};
+use std::path::Path;
+
+use crate::error::GitError;
+use crate::repository;
+
+/// Shortest abbreviation libgit2 accepts for an object id
+const MIN_ABBREV_LEN: usize = 4;
+
+/// Options for describing HEAD relative to the nearest tag, like `git describe`
+///
+/// The default matches plain `git describe`: only annotated tags, and an error if none is reachable.
+#[derive(Debug, Clone, Default)]
+pub struct DescribeOptions {
+    /// Consider lightweight tags too (`--tags`)
+    pub tags: bool,
+    /// Only consider tags matching this glob (`--match`)
+    pub pattern: Option<String>,
+    /// Fall back to the abbreviated commit id when no tag is reachable (`--always`)
+    pub always: bool,
+    /// Append this suffix when the working tree has changes (`--dirty`, usually `-dirty`)
+    pub dirty_suffix: Option<String>,
+    /// Length of abbreviated commit ids; `None` uses git's default
+    pub abbrev: Option<u32>,
+}
+
+/// Revision and object id helpers for Git repositories
+pub struct GitRevision;
+
+impl GitRevision {
+    /// Get the shortest unambiguous abbreviation of an object id
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `oid` - Object id to abbreviate
+    /// * `min_len` - Minimum abbreviation length; when `None` the repository's `core.abbrev` is used
+    pub fn short_sha(
+        repo_path: &Path,
+        oid: Oid,
+        min_len: Option<usize>,
+    ) -> Result<String, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let object = repo.find_object(oid, None)?;
+
+        let Some(min_len) = min_len else {
+            let short_id = object.short_id()?;
+            return Ok(short_id.as_str().unwrap_or_default().to_string());
+        };
+
+        let full = oid.to_string();
+        let start = min_len.clamp(MIN_ABBREV_LEN, full.len());
+        for len in start..full.len() {
+            let prefix = &full[..len];
+            match repo.find_object_by_prefix(prefix, None) {
+                Ok(_) => return Ok(prefix.to_string()),
+                Err(e) if e.code() == ErrorCode::Ambiguous => continue,
+                Err(e) => return Err(GitError::Git(e)),
+            }
+        }
+
+        Ok(full)
+    }
+
+    /// Describe HEAD relative to the nearest tag, e.g. `v1.2.0-3-gabc1234-dirty`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `options` - Which tags to use and how to format the result
+    pub fn describe(repo_path: &Path, options: &DescribeOptions) -> Result<String, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut describe_options = git2::DescribeOptions::new();
+        if options.tags {
+            describe_options.describe_tags();
+        }
+        if let Some(pattern) = &options.pattern {
+            describe_options.pattern(pattern);
+        }
+        describe_options.show_commit_oid_as_fallback(options.always);
+
+        let mut format_options = DescribeFormatOptions::new();
+        if let Some(dirty_suffix) = &options.dirty_suffix {
+            format_options.dirty_suffix(dirty_suffix);
+        }
+        if let Some(abbrev) = options.abbrev {
+            format_options.abbreviated_size(abbrev);
+        }
+
+        let description = repo.describe(&describe_options).map_err(|e| {
+            repository::shallow_boundary_error(&repo, repo_path, "describe", e, GitError::Git)
+        })?;
+        Ok(description.format(Some(&format_options))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo, shallow_clone};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_short_sha_default_abbrev() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let short = GitRevision::short_sha(temp_dir.path(), commit, None).unwrap();
+
+        assert_eq!(short, commit.to_string()[..7]);
+    }
+
+    #[test]
+    fn test_short_sha_respects_core_abbrev() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        repo.config().unwrap().set_i32("core.abbrev", 10).unwrap();
+
+        let short = GitRevision::short_sha(temp_dir.path(), commit, None).unwrap();
+
+        assert_eq!(short, commit.to_string()[..10]);
+    }
+
+    #[test]
+    fn test_short_sha_min_len() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let short = GitRevision::short_sha(temp_dir.path(), commit, Some(12)).unwrap();
+        let clamped = GitRevision::short_sha(temp_dir.path(), commit, Some(1)).unwrap();
+
+        assert_eq!(short, commit.to_string()[..12]);
+        assert_eq!(clamped, commit.to_string()[..4]);
+    }
+
+    #[test]
+    fn test_describe_tags_and_dirty() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let tagged = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        let tagged = repo.find_object(tagged, None).unwrap();
+        repo.tag_lightweight("v1.0.0", &tagged, false).unwrap();
+        let head = commit_file(&repo, "file.txt", "two\n", "Second");
+        std::fs::write(temp_dir.path().join("file.txt"), "dirty\n").unwrap();
+        let options = DescribeOptions {
+            tags: true,
+            dirty_suffix: Some("-dirty".to_string()),
+            abbrev: Some(7),
+            ..DescribeOptions::default()
+        };
+
+        let description = GitRevision::describe(temp_dir.path(), &options).unwrap();
+        let annotated_only = GitRevision::describe(temp_dir.path(), &DescribeOptions::default());
+
+        assert_eq!(
+            description,
+            format!("v1.0.0-1-g{}-dirty", &head.to_string()[..7])
+        );
+        assert!(annotated_only.is_err());
+    }
+
+    #[test]
+    fn test_describe_always_falls_back_to_sha() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        let options = DescribeOptions {
+            always: true,
+            abbrev: Some(7),
+            ..DescribeOptions::default()
+        };
+
+        let description = GitRevision::describe(temp_dir.path(), &options).unwrap();
+
+        assert_eq!(description, commit.to_string()[..7]);
+    }
+
+    #[test]
+    fn test_describe_tag_beyond_shallow_boundary() {
+        let temp_dir = TempDir::new().unwrap();
+        let upstream = init_repo(&temp_dir.path().join("upstream"));
+        let tagged = commit_file(&upstream, "file.txt", "one\n", "First");
+        let tagged = upstream.find_object(tagged, None).unwrap();
+        let signature = upstream.signature().unwrap();
+        upstream
+            .tag("v1.0.0", &tagged, &signature, "v1.0.0", false)
+            .unwrap();
+        commit_file(&upstream, "file.txt", "two\n", "Second");
+        let local = shallow_clone(temp_dir.path());
+
+        let result = GitRevision::describe(local.workdir().unwrap(), &DescribeOptions::default());
+
+        assert!(matches!(result, Err(GitError::ShallowBoundary { .. })));
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+
+use crate::fetch;
+
+/// What an OpenSSH client config file sets for one host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HostSettings {
+    /// `HostName`: the real host name the alias stands for
+    pub(crate) host_name: Option<String>,
+    /// `User`: the username to log in as
+    pub(crate) user: Option<String>,
+    /// `IdentityFile`: private keys to try, in order
+    pub(crate) identity_files: Vec<PathBuf>,
+}
+
+/// A `Host` block and the settings in it
+#[derive(Debug, Clone)]
+struct HostBlock {
+    /// Host patterns; `None` for settings before the first `Host` line, which apply to every host
+    patterns: Option<Vec<String>>,
+    settings: HostSettings,
+}
+
+/// The `Host` blocks of an OpenSSH client config file such as `~/.ssh/config`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SshHostConfig {
+    blocks: Vec<HostBlock>,
+}
+
+impl SshHostConfig {
+    /// Read the config file at `path`; a missing file configures no hosts
+    ///
+    /// `~` and `%d` in identity file paths expand to `home`, and relative paths are taken
+    /// relative to it, like OpenSSH does.
+    pub(crate) fn load(path: &Path, home: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(Self::parse(&contents, home)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parse the contents of a config file, see `load`
+    ///
+    /// Only `Host`, `HostName`, `User` and `IdentityFile` are understood; other keywords are
+    /// ignored, and so are `Match` blocks since their conditions aren't evaluated.
+    pub(crate) fn parse(contents: &str, home: &Path) -> Self {
+        let mut blocks = vec![HostBlock {
+            patterns: None,
+            settings: HostSettings::default(),
+        }];
+        // Whether lines currently belong to a `Match` block, whose settings are skipped
+        let mut in_match = false;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, value) = match line.find(|c: char| c.is_whitespace() || c == '=') {
+                Some(end) => (
+                    &line[..end],
+                    line[end..].trim_start_matches(|c: char| c.is_whitespace() || c == '='),
+                ),
+                None => (line, ""),
+            };
+            let value = value.trim();
+
+            match keyword.to_lowercase().as_str() {
+                "host" => {
+                    in_match = false;
+                    blocks.push(HostBlock {
+                        patterns: Some(value.split_whitespace().map(str::to_lowercase).collect()),
+                        settings: HostSettings::default(),
+                    });
+                }
+                "match" => in_match = true,
+                _ if in_match || value.is_empty() => {}
+                "hostname" => {
+                    let settings = &mut blocks.last_mut().expect("a block").settings;
+                    settings
+                        .host_name
+                        .get_or_insert_with(|| unquote(value).to_string());
+                }
+                "user" => {
+                    let settings = &mut blocks.last_mut().expect("a block").settings;
+                    settings
+                        .user
+                        .get_or_insert_with(|| unquote(value).to_string());
+                }
+                "identityfile" => {
+                    let settings = &mut blocks.last_mut().expect("a block").settings;
+                    settings
+                        .identity_files
+                        .push(expand_identity_path(unquote(value), home));
+                }
+                _ => {}
+            }
+        }
+
+        Self { blocks }
+    }
+
+    /// Settings for `host`, combined from every block matching it like OpenSSH does
+    ///
+    /// The first `HostName` and `User` found win, while identity files from all matching
+    /// blocks are tried in the order they appear.
+    pub(crate) fn resolve(&self, host: &str) -> HostSettings {
+        let host = host.to_lowercase();
+        let mut resolved = HostSettings::default();
+        for block in &self.blocks {
+            let matches = match &block.patterns {
+                Some(patterns) => patterns_match(patterns, &host),
+                None => true,
+            };
+            if !matches {
+                continue;
+            }
+
+            if resolved.host_name.is_none() {
+                resolved.host_name = block.settings.host_name.clone();
+            }
+            if resolved.user.is_none() {
+                resolved.user = block.settings.user.clone();
+            }
+            resolved
+                .identity_files
+                .extend(block.settings.identity_files.iter().cloned());
+        }
+        resolved
+    }
+}
+
+/// Whether the `Host` patterns match `host`; a matching `!` pattern excludes it
+fn patterns_match(patterns: &[String], host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if fetch::glob_matches(negated, host) {
+                return false;
+            }
+        } else {
+            matched |= fetch::glob_matches(pattern, host);
+        }
+    }
+    matched
+}
+
+/// Strip the double quotes around a value, if it has them
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Expand `~` and `%d` to `home` and make relative paths relative to it
+fn expand_identity_path(path: &str, home: &Path) -> PathBuf {
+    let home_str = home.to_string_lossy();
+    let path = path.replace("%d", &home_str);
+    let path = match path.strip_prefix('~') {
+        Some(rest) => format!("{home_str}{rest}"),
+        None => path,
+    };
+
+    let path = PathBuf::from(path);
+    if path.is_relative() {
+        home.join(path)
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_combines_matching_blocks() {
+        let config = SshHostConfig::parse(
+            "# Work account\n\
+             Host work *.corp.example.com\n\
+             \x20   HostName git.corp.example.com\n\
+             \x20   User deploy\n\
+             \x20   IdentityFile ~/.ssh/id_work\n\
+             \n\
+             Match host legacy\n\
+             \x20   User ignored\n\
+             \n\
+             Host * !private.example.com\n\
+             \x20   User=fallback\n\
+             \x20   IdentityFile \"keys/id_default\"\n",
+            Path::new("/home/test"),
+        );
+
+        assert_eq!(
+            config.resolve("WORK"),
+            HostSettings {
+                host_name: Some("git.corp.example.com".to_string()),
+                user: Some("deploy".to_string()),
+                identity_files: vec![
+                    PathBuf::from("/home/test/.ssh/id_work"),
+                    PathBuf::from("/home/test/keys/id_default"),
+                ],
+            }
+        );
+        assert_eq!(
+            config.resolve("github.com"),
+            HostSettings {
+                host_name: None,
+                user: Some("fallback".to_string()),
+                identity_files: vec![PathBuf::from("/home/test/keys/id_default")],
+            }
+        );
+        assert_eq!(
+            config.resolve("private.example.com"),
+            HostSettings::default()
+        );
+    }
+
+    #[test]
+    fn test_load_missing_file_configures_no_hosts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let config = SshHostConfig::load(&temp_dir.path().join("config"), temp_dir.path()).unwrap();
+
+        assert_eq!(config.resolve("github.com"), HostSettings::default());
+    }
+}
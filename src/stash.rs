@@ -0,0 +1,99 @@
+use git2::{ErrorCode, Oid, Repository};
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// Stash operations for Git repositories
+pub struct GitStash;
+
+impl GitStash {
+    /// Stash uncommitted changes to tracked files, like `git stash push`
+    ///
+    /// The stash is recorded as the repository's configured `user.name` and `user.email`.
+    /// Untracked files are left in place.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `message` - Message describing the stash, or `None` for git's default
+    ///
+    /// # Returns
+    /// The stash commit, or `None` if there were no changes to stash
+    pub fn stash_save(repo_path: &Path, message: Option<&str>) -> Result<Option<Oid>, GitError> {
+        let mut repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let signature = repo.signature()?;
+        match repo.stash_save(&signature, message.unwrap_or(""), None) {
+            Ok(stash) => Ok(Some(stash)),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(GitError::Git(e)),
+        }
+    }
+
+    /// Apply the most recent stash and drop it, like `git stash pop`
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Errors
+    /// Fails if there is no stash, or if applying it conflicts with the working tree, in
+    /// which case the stash is kept
+    pub fn stash_pop(repo_path: &Path) -> Result<(), GitError> {
+        let mut repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        repo.stash_pop(0, None)?;
+        Ok(())
+    }
+
+    /// Check if the index or working tree has changes to tracked files that `stash_save` would stash
+    pub(crate) fn has_changes(repo: &Repository) -> Result<bool, GitError> {
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+        Ok(!repo.statuses(Some(&mut options))?.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stash_save_and_pop() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "First");
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "local\n").unwrap();
+
+        let stash = GitStash::stash_save(temp_dir.path(), Some("work in progress")).unwrap();
+
+        assert!(stash.is_some());
+        assert!(!GitStash::has_changes(&repo).unwrap());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "one\n");
+
+        GitStash::stash_pop(temp_dir.path()).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "local\n");
+        assert!(GitStash::has_changes(&repo).unwrap());
+    }
+
+    #[test]
+    fn test_stash_save_without_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "First");
+
+        let stash = GitStash::stash_save(temp_dir.path(), None).unwrap();
+
+        assert_eq!(stash, None);
+        assert!(GitStash::stash_pop(temp_dir.path()).is_err());
+    }
+}
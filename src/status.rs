@@ -0,0 +1,249 @@
+use git2::{Pathspec, PathspecFlags, Repository, Status, StatusOptions};
+use std::path::{Path, PathBuf};
+
+use crate::error::GitError;
+
+/// How a file differs from HEAD, in the index or the working tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file isn't in HEAD, whether staged or untracked
+    New,
+    /// The file's contents or type changed
+    Modified,
+    /// The file was removed
+    Deleted,
+    /// The file was moved; the entry's path is the new path
+    Renamed,
+    /// The file has unresolved merge conflicts
+    Conflicted,
+}
+
+/// A changed file reported by `status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    /// Path relative to the repository root
+    pub path: PathBuf,
+    /// How the file changed
+    pub status: FileStatus,
+}
+
+/// Options controlling what counts as a dirty working tree
+#[derive(Debug, Clone, Default)]
+pub struct DirtyOptions {
+    /// Pathspecs whose changes never count, e.g. `config/generated.yml` or `logs/*`
+    pub ignore: Vec<String>,
+    /// Count untracked files as changes
+    pub include_untracked: bool,
+}
+
+/// Working tree status operations for Git repositories
+pub struct GitStatus;
+
+impl GitStatus {
+    /// Check if the index or working tree has changes, apart from the ignored paths
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `options` - Which paths and kinds of changes to disregard
+    pub fn is_dirty(repo_path: &Path, options: &DirtyOptions) -> Result<bool, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut status_options = StatusOptions::new();
+        status_options
+            .include_untracked(options.include_untracked)
+            .recurse_untracked_dirs(options.include_untracked)
+            .include_ignored(false);
+
+        let ignore = Pathspec::new(options.ignore.iter())?;
+        let statuses = repo.statuses(Some(&mut status_options))?;
+
+        let dirty = statuses.iter().any(|entry| {
+            if entry.status().is_empty() || entry.status().contains(Status::IGNORED) {
+                return false;
+            }
+
+            let path = String::from_utf8_lossy(entry.path_bytes()).into_owned();
+            options.ignore.is_empty()
+                || !ignore.matches_path(Path::new(&path), PathspecFlags::DEFAULT)
+        });
+
+        Ok(dirty)
+    }
+
+    /// List the files whose index or working tree state differs from HEAD, like `git status`
+    ///
+    /// Untracked files are listed as `New`; ignored files aren't listed. Staged and unstaged
+    /// changes to the same file are reported once, with conflicts taking precedence.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn status(repo_path: &Path) -> Result<Vec<StatusEntry>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let mut status_options = StatusOptions::new();
+        status_options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .renames_head_to_index(true);
+
+        let statuses = repo.statuses(Some(&mut status_options))?;
+        let entries = statuses
+            .iter()
+            .filter_map(|entry| {
+                let status = FileStatus::from_git2(entry.status())?;
+                // A rename's new path is on the delta; the entry's own path is the old one
+                let path = entry
+                    .head_to_index()
+                    .filter(|_| status == FileStatus::Renamed)
+                    .and_then(|delta| delta.new_file().path().map(Path::to_path_buf))
+                    .unwrap_or_else(|| {
+                        PathBuf::from(String::from_utf8_lossy(entry.path_bytes()).into_owned())
+                    });
+                Some(StatusEntry { path, status })
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Check if the index and working tree match HEAD, untracked files included
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn is_clean(repo_path: &Path) -> Result<bool, GitError> {
+        Ok(Self::status(repo_path)?.is_empty())
+    }
+}
+
+impl FileStatus {
+    /// Classify git2 status flags, `None` for unchanged or ignored files
+    fn from_git2(status: Status) -> Option<Self> {
+        if status.is_conflicted() {
+            Some(Self::Conflicted)
+        } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+            Some(Self::Renamed)
+        } else if status.intersects(Status::INDEX_NEW | Status::WT_NEW) {
+            Some(Self::New)
+        } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+            Some(Self::Deleted)
+        } else if status.intersects(
+            Status::INDEX_MODIFIED
+                | Status::WT_MODIFIED
+                | Status::INDEX_TYPECHANGE
+                | Status::WT_TYPECHANGE,
+        ) {
+            Some(Self::Modified)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_status_lists_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "modified.txt", "one\n", "Add modified");
+        commit_file(&repo, "deleted.txt", "one\n", "Add deleted");
+        commit_file(
+            &repo,
+            "old.txt",
+            "a file long enough to be detected as renamed\n",
+            "Add old",
+        );
+        std::fs::write(temp_dir.path().join("modified.txt"), "two\n").unwrap();
+        std::fs::remove_file(temp_dir.path().join("deleted.txt")).unwrap();
+        std::fs::write(temp_dir.path().join("new.txt"), "new\n").unwrap();
+        std::fs::rename(
+            temp_dir.path().join("old.txt"),
+            temp_dir.path().join("renamed.txt"),
+        )
+        .unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("old.txt")).unwrap();
+        index.add_path(Path::new("renamed.txt")).unwrap();
+        index.write().unwrap();
+
+        let mut entries = GitStatus::status(temp_dir.path()).unwrap();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let entry = |path: &str, status| StatusEntry {
+            path: PathBuf::from(path),
+            status,
+        };
+        assert_eq!(
+            entries,
+            vec![
+                entry("deleted.txt", FileStatus::Deleted),
+                entry("modified.txt", FileStatus::Modified),
+                entry("new.txt", FileStatus::New),
+                entry("renamed.txt", FileStatus::Renamed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_clean() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+
+        let clean = GitStatus::is_clean(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("file.txt"), "two\n").unwrap();
+        let modified = GitStatus::is_clean(temp_dir.path()).unwrap();
+
+        assert!(clean);
+        assert!(!modified);
+    }
+
+    #[test]
+    fn test_is_dirty_ignores_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "config/generated.yml", "a: 1\n", "Add config");
+        commit_file(&repo, "src/main.rs", "fn main() {}\n", "Add main");
+        std::fs::write(temp_dir.path().join("config/generated.yml"), "a: 2\n").unwrap();
+        let options = DirtyOptions {
+            ignore: vec!["config/*".to_string()],
+            ..DirtyOptions::default()
+        };
+
+        let ignored = GitStatus::is_dirty(temp_dir.path(), &options).unwrap();
+        let unfiltered = GitStatus::is_dirty(temp_dir.path(), &DirtyOptions::default()).unwrap();
+
+        assert!(!ignored);
+        assert!(unfiltered);
+    }
+
+    #[test]
+    fn test_is_dirty_untracked_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(&repo, "file.txt", "one\n", "Initial commit");
+        std::fs::write(temp_dir.path().join("new.txt"), "new\n").unwrap();
+        let options = DirtyOptions {
+            include_untracked: true,
+            ..DirtyOptions::default()
+        };
+
+        let without_untracked =
+            GitStatus::is_dirty(temp_dir.path(), &DirtyOptions::default()).unwrap();
+        let with_untracked = GitStatus::is_dirty(temp_dir.path(), &options).unwrap();
+
+        assert!(!without_untracked);
+        assert!(with_untracked);
+    }
+}
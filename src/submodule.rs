@@ -0,0 +1,142 @@
+use git2::Repository;
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// A submodule declared in a repository's `.gitmodules` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitmoduleEntry {
+    /// Submodule name (the `<name>` in `[submodule "<name>"]`)
+    pub name: String,
+    /// Path of the submodule relative to the repository root
+    pub path: String,
+    /// URL the submodule is cloned from
+    pub url: String,
+    /// Branch the submodule tracks, if configured
+    pub branch: Option<String>,
+}
+
+/// Submodule operations for Git repositories
+pub struct GitSubmodule;
+
+impl GitSubmodule {
+    /// Read the submodules declared in `.gitmodules` without initializing or cloning them
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// Entries in the order they appear in `.gitmodules`; empty if the file doesn't exist
+    pub fn parse_gitmodules(repo_path: &Path) -> Result<Vec<GitmoduleEntry>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let Some(workdir) = repo.workdir() else {
+            return Ok(Vec::new());
+        };
+
+        let gitmodules_path = workdir.join(".gitmodules");
+        if !gitmodules_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let config = git2::Config::open(&gitmodules_path)?;
+        let mut entries: Vec<GitmoduleEntry> = Vec::new();
+
+        let mut config_entries = config.entries(Some(r"^submodule\..*"))?;
+        while let Some(config_entry) = config_entries.next() {
+            let config_entry = config_entry?;
+            let (Some(key), Some(value)) = (config_entry.name(), config_entry.value()) else {
+                continue;
+            };
+
+            // Keys look like submodule.<name>.<field>, where <name> may itself contain dots
+            let Some((name, field)) = key
+                .strip_prefix("submodule.")
+                .and_then(|rest| rest.rsplit_once('.'))
+            else {
+                continue;
+            };
+
+            let index = match entries.iter().position(|entry| entry.name == name) {
+                Some(index) => index,
+                None => {
+                    entries.push(GitmoduleEntry {
+                        name: name.to_string(),
+                        path: String::new(),
+                        url: String::new(),
+                        branch: None,
+                    });
+                    entries.len() - 1
+                }
+            };
+
+            let entry = &mut entries[index];
+            match field {
+                "path" => entry.path = value.to_string(),
+                "url" => entry.url = value.to_string(),
+                "branch" => entry.branch = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_gitmodules() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        commit_file(
+            &repo,
+            ".gitmodules",
+            "[submodule \"libs/core\"]\n\
+             \tpath = vendor/core\n\
+             \turl = git@github.com:example/core.git\n\
+             \tbranch = stable\n\
+             [submodule \"docs\"]\n\
+             \tpath = docs\n\
+             \turl = https://github.com/example/docs.git\n",
+            "Add submodules",
+        );
+
+        let entries = GitSubmodule::parse_gitmodules(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                GitmoduleEntry {
+                    name: "libs/core".to_string(),
+                    path: "vendor/core".to_string(),
+                    url: "git@github.com:example/core.git".to_string(),
+                    branch: Some("stable".to_string()),
+                },
+                GitmoduleEntry {
+                    name: "docs".to_string(),
+                    path: "docs".to_string(),
+                    url: "https://github.com/example/docs.git".to_string(),
+                    branch: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_gitmodules_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        init_repo(temp_dir.path());
+
+        let entries = GitSubmodule::parse_gitmodules(temp_dir.path()).unwrap();
+
+        assert!(entries.is_empty());
+    }
+}
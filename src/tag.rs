@@ -0,0 +1,114 @@
+use git2::Repository;
+use std::path::Path;
+
+use crate::error::GitError;
+
+/// Tag operations for Git repositories
+pub struct GitTag;
+
+impl GitTag {
+    /// Tag `target_rev`, like `git tag` (lightweight) or `git tag -a` (annotated)
+    ///
+    /// Annotated tags are signed as the repository's configured `user.name` and `user.email`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Name of the tag, e.g. `v1.2.0`
+    /// * `target_rev` - Revision to tag (branch, SHA, `HEAD`, ...)
+    /// * `message` - Message of an annotated tag, or `None` for a lightweight tag
+    ///
+    /// # Errors
+    /// Returns `RevisionNotFound` if `target_rev` doesn't resolve, and fails without moving
+    /// the tag if it already exists
+    pub fn create_tag(
+        repo_path: &Path,
+        name: &str,
+        target_rev: &str,
+        message: Option<&str>,
+    ) -> Result<(), GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let target = repo
+            .revparse_single(target_rev)
+            .map_err(|e| GitError::RevisionNotFound {
+                rev: target_rev.to_string(),
+                path: repo_path.to_path_buf(),
+                source: e,
+            })?;
+
+        match message {
+            Some(message) => {
+                let signature = repo.signature()?;
+                repo.tag(name, &target, &signature, message, false)?;
+            }
+            None => {
+                repo.tag_lightweight(name, &target, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List tag names, sorted
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    pub fn list_tags(repo_path: &Path) -> Result<Vec<String>, GitError> {
+        let repo = Repository::open(repo_path).map_err(|e| GitError::OpenFailed {
+            path: repo_path.to_path_buf(),
+            source: e,
+        })?;
+
+        let tags = repo.tag_names(None)?;
+        Ok(tags.iter().flatten().map(str::to_string).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_and_list_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        commit_file(&repo, "file.txt", "two\n", "Second");
+
+        GitTag::create_tag(temp_dir.path(), "v1.0.0", "HEAD~1", None).unwrap();
+        GitTag::create_tag(temp_dir.path(), "v1.1.0", "HEAD", Some("Release 1.1.0")).unwrap();
+
+        assert_eq!(
+            GitTag::list_tags(temp_dir.path()).unwrap(),
+            vec!["v1.0.0".to_string(), "v1.1.0".to_string()]
+        );
+        let lightweight = repo.find_reference("refs/tags/v1.0.0").unwrap();
+        assert_eq!(lightweight.target(), Some(first));
+        let annotated = repo
+            .find_reference("refs/tags/v1.1.0")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(annotated.message(), Some("Release 1.1.0"));
+        assert_eq!(annotated.tagger().unwrap().name(), Some("Test User"));
+    }
+
+    #[test]
+    fn test_create_existing_tag_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let first = commit_file(&repo, "file.txt", "one\n", "First");
+        GitTag::create_tag(temp_dir.path(), "v1.0.0", "HEAD", None).unwrap();
+        commit_file(&repo, "file.txt", "two\n", "Second");
+
+        let result = GitTag::create_tag(temp_dir.path(), "v1.0.0", "HEAD", None);
+
+        assert!(result.is_err());
+        assert_eq!(repo.refname_to_id("refs/tags/v1.0.0").unwrap(), first);
+    }
+}
@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{Commit, Oid, Repository, RepositoryInitOptions, Signature};
+
+/// Initialize a repository on `main` with a local user identity configured
+pub fn init_repo(path: &Path) -> Repository {
+    let mut options = RepositoryInitOptions::new();
+    options.initial_head("main");
+
+    let repo = Repository::init_opts(path, &options).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    repo
+}
+
+/// Write a file into the working tree, stage it and commit it on HEAD
+pub fn commit_file(repo: &Repository, name: &str, contents: &str, message: &str) -> Oid {
+    let signature = repo.signature().unwrap();
+    commit_file_as(repo, &signature, name, contents, message)
+}
+
+/// Like `commit_file`, but authored and committed by `signature`
+pub fn commit_file_as(
+    repo: &Repository,
+    signature: &Signature,
+    name: &str,
+    contents: &str,
+    message: &str,
+) -> Oid {
+    let workdir = repo.workdir().unwrap();
+    let file_path = workdir.join(name);
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(&file_path, contents).unwrap();
+
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(name)).unwrap();
+    index.write().unwrap();
+
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+
+    let parents: Vec<Commit> = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parent_refs: Vec<&Commit> = parents.iter().collect();
+
+    repo.commit(
+        Some("HEAD"),
+        signature,
+        signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+    .unwrap()
+}
+
+/// Create an upstream repository with one commit and a clone of it whose `origin` points upstream
+pub fn upstream_and_clone(root: &Path) -> (Repository, Repository) {
+    let upstream_path = root.join("upstream");
+    let upstream = init_repo(&upstream_path);
+    commit_file(&upstream, "README.md", "initial\n", "Initial commit");
+
+    let local = Repository::clone(upstream_path.to_str().unwrap(), root.join("local")).unwrap();
+    let mut config = local.config().unwrap();
+    config.set_str("user.name", "Test User").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+
+    (upstream, local)
+}
+
+/// Clone `root/upstream` into `root/shallow` with only its latest commit, using the git command line
+pub fn shallow_clone(root: &Path) -> Repository {
+    let upstream_url = format!("file://{}", root.join("upstream").display());
+    crate::command::run_git_checked(
+        root,
+        &["clone", "--quiet", "--depth", "1", &upstream_url, "shallow"],
+    )
+    .unwrap();
+
+    Repository::open(root.join("shallow")).unwrap()
+}
+
+/// A `git daemon` serving the repositories under a directory, killed on drop
+///
+/// libgit2's local transport copies objects straight from the other object database, so
+/// tests of fetch negotiation need a smart transport.
+pub struct GitDaemon {
+    child: std::process::Child,
+    port: u16,
+}
+
+impl GitDaemon {
+    /// Serve every repository under `base_path` over `git://`
+    pub fn serve(base_path: &Path) -> Self {
+        let exec_path = crate::command::run_git_checked(base_path, &["--exec-path"]).unwrap();
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        // Run git-daemon itself rather than through the `git` wrapper, so killing the child
+        // stops the daemon instead of orphaning it
+        let mut child = std::process::Command::new(Path::new(exec_path.trim()).join("git-daemon"))
+            .arg("--export-all")
+            .arg("--reuseaddr")
+            .arg("--listen=127.0.0.1")
+            .arg(format!("--port={port}"))
+            .arg(format!("--base-path={}", base_path.display()))
+            .arg(base_path)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while std::net::TcpStream::connect(("127.0.0.1", port)).is_err() {
+            if std::time::Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                panic!("git daemon did not listen on port {port}");
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        Self { child, port }
+    }
+
+    /// URL of the repository at `name` under the served directory
+    pub fn url(&self, name: &str) -> String {
+        format!("git://127.0.0.1:{}/{name}", self.port)
+    }
+}
+
+impl Drop for GitDaemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A smart HTTP server running `git http-backend` for the repositories under a directory
+///
+/// libgit2 1.7 only completes shallow fetches over stateless transports, so depth tests
+/// serve over HTTP rather than `git://`. Requests are answered one at a time on a
+/// background thread that stops on drop.
+pub struct GitHttpServer {
+    port: u16,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl GitHttpServer {
+    /// Serve every repository under `base_path` over `http://`
+    pub fn serve(base_path: &Path) -> Self {
+        let exec_path = crate::command::run_git_checked(base_path, &["--exec-path"]).unwrap();
+        let backend = Path::new(exec_path.trim()).join("git-http-backend");
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let project_root = base_path.to_path_buf();
+        let thread_stop = stop.clone();
+        let thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stop.load(std::sync::atomic::Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    // A failed request shows up as a failed fetch in the test itself
+                    let _ = Self::respond(&backend, &project_root, stream);
+                }
+            }
+        });
+
+        Self {
+            port,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// URL of the repository at `name` under the served directory
+    pub fn url(&self, name: &str) -> String {
+        format!("http://127.0.0.1:{}/{name}", self.port)
+    }
+
+    /// Answer one request by running it through `git http-backend` as a CGI script
+    fn respond(
+        backend: &Path,
+        project_root: &Path,
+        stream: std::net::TcpStream,
+    ) -> std::io::Result<()> {
+        use std::io::{BufRead, Read, Write};
+
+        let mut reader = std::io::BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("GET").to_string();
+        let target = parts.next().unwrap_or("/").to_string();
+        let (path_info, query) = target.split_once('?').unwrap_or((&target, ""));
+
+        let mut content_type = String::new();
+        let mut content_length = 0;
+        let mut chunked = false;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                let value = value.trim();
+                match name.to_ascii_lowercase().as_str() {
+                    "content-type" => content_type = value.to_string(),
+                    "content-length" => content_length = value.parse().unwrap_or(0),
+                    "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut body = Vec::new();
+        if chunked {
+            loop {
+                let mut size_line = String::new();
+                reader.read_line(&mut size_line)?;
+                let size = usize::from_str_radix(size_line.trim(), 16).unwrap_or(0);
+                let mut chunk = vec![0; size + 2];
+                reader.read_exact(&mut chunk)?;
+                if size == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk[..size]);
+            }
+        } else {
+            body.resize(content_length, 0);
+            reader.read_exact(&mut body)?;
+        }
+
+        let mut child = std::process::Command::new(backend)
+            .env("GIT_PROJECT_ROOT", project_root)
+            .env("GIT_HTTP_EXPORT_ALL", "1")
+            .env("REQUEST_METHOD", &method)
+            .env("PATH_INFO", path_info)
+            .env("QUERY_STRING", query)
+            .env("CONTENT_TYPE", &content_type)
+            .env("CONTENT_LENGTH", body.len().to_string())
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+        child.stdin.take().unwrap().write_all(&body)?;
+        let output = child.wait_with_output()?;
+
+        // CGI output is headers (with an optional Status) and a blank line, then the body
+        let stdout = output.stdout;
+        let split = stdout
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .map(|index| (index, index + 4))
+            .unwrap_or((stdout.len(), stdout.len()));
+        let headers = String::from_utf8_lossy(&stdout[..split.0]);
+        let response_body = &stdout[split.1..];
+
+        let mut status = "200 OK".to_string();
+        let mut response = Vec::new();
+        for header in headers.lines() {
+            match header.strip_prefix("Status:") {
+                Some(value) => status = value.trim().to_string(),
+                None => write!(response, "{header}\r\n")?,
+            }
+        }
+
+        let mut stream = stream;
+        write!(stream, "HTTP/1.1 {status}\r\n")?;
+        stream.write_all(&response)?;
+        write!(
+            stream,
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            response_body.len()
+        )?;
+        stream.write_all(response_body)?;
+        stream.flush()
+    }
+}
+
+impl Drop for GitHttpServer {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+        // Wake the accept loop so it sees the stop flag
+        let _ = std::net::TcpStream::connect(("127.0.0.1", self.port));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::RwLock;
+
+/// Severity of a libgit2 trace message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TraceLevel {
+    /// Severe errors that may impact the program's execution
+    Fatal,
+    /// Errors that do not impact the program's execution
+    Error,
+    /// Warnings that suggest abnormal data
+    Warn,
+    /// Informational messages about program execution
+    Info,
+    /// Detailed data that allows for debugging
+    Debug,
+    /// Exceptionally detailed debugging data
+    Trace,
+}
+
+/// Handler receiving libgit2 trace messages
+type TraceHandler = Box<dyn Fn(TraceLevel, &str) + Send + Sync>;
+
+/// The registered handler; libgit2 only supports one process-wide trace callback
+static TRACE_HANDLER: RwLock<Option<TraceHandler>> = RwLock::new(None);
+
+/// Route libgit2's internal trace output to `handler`
+///
+/// Tracing is process-wide: the handler receives messages from every repository and thread,
+/// and replaces any previously registered handler. Messages less severe than `level` are dropped.
+///
+/// # Arguments
+/// * `level` - Least severe level to report
+/// * `handler` - Called with each trace message
+pub fn enable_trace<F>(level: TraceLevel, handler: F)
+where
+    F: Fn(TraceLevel, &str) + Send + Sync + 'static,
+{
+    *TRACE_HANDLER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(handler));
+    git2::trace_set(level.into(), dispatch);
+}
+
+/// Stop routing libgit2 trace output and drop the registered handler
+pub fn disable_trace() {
+    git2::trace_set(git2::TraceLevel::None, dispatch);
+    *TRACE_HANDLER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Forward a trace message from libgit2 to the registered handler
+fn dispatch(level: git2::TraceLevel, message: &str) {
+    let Some(level) = TraceLevel::from_git2(level) else {
+        return;
+    };
+
+    if let Some(handler) = TRACE_HANDLER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+    {
+        handler(level, message);
+    }
+}
+
+impl TraceLevel {
+    /// Convert from git2's level, which also has a `None` level for "tracing off"
+    fn from_git2(level: git2::TraceLevel) -> Option<Self> {
+        match level {
+            git2::TraceLevel::None => None,
+            git2::TraceLevel::Fatal => Some(Self::Fatal),
+            git2::TraceLevel::Error => Some(Self::Error),
+            git2::TraceLevel::Warn => Some(Self::Warn),
+            git2::TraceLevel::Info => Some(Self::Info),
+            git2::TraceLevel::Debug => Some(Self::Debug),
+            git2::TraceLevel::Trace => Some(Self::Trace),
+        }
+    }
+}
+
+impl From<TraceLevel> for git2::TraceLevel {
+    fn from(level: TraceLevel) -> Self {
+        match level {
+            TraceLevel::Fatal => Self::Fatal,
+            TraceLevel::Error => Self::Error,
+            TraceLevel::Warn => Self::Warn,
+            TraceLevel::Info => Self::Info,
+            TraceLevel::Debug => Self::Debug,
+            TraceLevel::Trace => Self::Trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_trace_routes_to_handler() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+
+        enable_trace(TraceLevel::Debug, move |level, message| {
+            sink.lock().unwrap().push((level, message.to_string()));
+        });
+        dispatch(git2::TraceLevel::Info, "negotiating");
+        disable_trace();
+        dispatch(git2::TraceLevel::Info, "after disable");
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![(TraceLevel::Info, "negotiating".to_string())]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use git2::{ObjectType, Oid, Repository};
+use std::path::Path;
+
+use crate::command;
+use crate::error::GitError;
+
+/// Shortest GPG key id accepted in an allow-list (a long key id)
+const MIN_GPG_KEY_ID_LEN: usize = 16;
+
+/// Signature verification for commits and tags
+pub struct GitVerify;
+
+impl GitVerify {
+    /// Verify that an object carries a valid signature from one of the allowed keys
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `repo` - The opened repository
+    /// * `object_id` - Id of the commit or annotated tag to verify
+    /// * `allowed_keys` - GPG fingerprints/long key ids or SSH `SHA256:` fingerprints to trust
+    ///
+    /// # Errors
+    /// Returns `SignatureVerificationFailed` if the object is unsigned, the signature is
+    /// invalid, or it was made by a key that isn't allowed
+    pub fn verify_object(
+        repo_path: &Path,
+        repo: &Repository,
+        object_id: Oid,
+        allowed_keys: &[&str],
+    ) -> Result<(), GitError> {
+        let object = repo.find_object(object_id, None)?;
+        let target = object_id.to_string();
+
+        let subcommand = match object.kind() {
+            Some(ObjectType::Tag) => "verify-tag",
+            Some(ObjectType::Commit) => {
+                // Check for a signature up front so unsigned commits get a clear reason
+                if repo.extract_signature(&object_id, None).is_err() {
+                    return Err(Self::failure(repo_path, &target, "no signature found"));
+                }
+                "verify-commit"
+            }
+            _ => {
+                return Err(Self::failure(
+                    repo_path,
+                    &target,
+                    "only commits and annotated tags can be verified",
+                ))
+            }
+        };
+
+        let output = command::run_git(repo_path, &[subcommand, "--raw", &target])?;
+        let report = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            let reason = if report.trim().is_empty() {
+                "signature is not valid".to_string()
+            } else {
+                report.trim().to_string()
+            };
+            return Err(Self::failure(repo_path, &target, &reason));
+        }
+
+        let signing_keys = Self::signing_keys(&report);
+        if !signing_keys
+            .iter()
+            .any(|key| Self::key_allowed(key, allowed_keys))
+        {
+            return Err(Self::failure(
+                repo_path,
+                &target,
+                "signed by a key that is not in the allowed list",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Extract signing key identifiers from `git verify-* --raw` output
+    fn signing_keys(report: &str) -> Vec<String> {
+        let mut keys = Vec::new();
+
+        for line in report.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                // GPG: subkey fingerprint, then the primary key fingerprint last
+                ["[GNUPG:]", "VALIDSIG", fingerprint, rest @ ..] => {
+                    keys.push(fingerprint.to_string());
+                    if let Some(primary) = rest.last() {
+                        keys.push(primary.to_string());
+                    }
+                }
+                ["[GNUPG:]", "GOODSIG", key_id, ..] => keys.push(key_id.to_string()),
+                // SSH: Good "git" signature for <principal> with <type> key SHA256:...
+                ["Good", ..] => keys.extend(
+                    fields
+                        .iter()
+                        .filter(|field| field.starts_with("SHA256:"))
+                        .map(|field| field.to_string()),
+                ),
+                _ => {}
+            }
+        }
+
+        keys
+    }
+
+    /// Check a signing key against the allowed keys
+    ///
+    /// SSH fingerprints must match exactly; GPG fingerprints and long key ids (at least 16 hex
+    /// digits) match as fingerprint suffixes. Short 8-digit key ids are rejected since colliding
+    /// keys are easy to generate.
+    fn key_allowed(signing_key: &str, allowed_keys: &[&str]) -> bool {
+        if signing_key.starts_with("SHA256:") {
+            return allowed_keys.contains(&signing_key);
+        }
+
+        let signing_key = signing_key.to_ascii_uppercase();
+        allowed_keys.iter().any(|allowed| {
+            let allowed = allowed.replace(' ', "").to_ascii_uppercase();
+            allowed.len() >= MIN_GPG_KEY_ID_LEN
+                && allowed.chars().all(|c| c.is_ascii_hexdigit())
+                && signing_key.ends_with(&allowed)
+        })
+    }
+
+    fn failure(repo_path: &Path, target: &str, reason: &str) -> GitError {
+        GitError::SignatureVerificationFailed {
+            target: target.to_string(),
+            path: repo_path.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_file, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_verify_unsigned_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = init_repo(temp_dir.path());
+        let commit = commit_file(&repo, "file.txt", "one\n", "Unsigned");
+
+        let result = GitVerify::verify_object(temp_dir.path(), &repo, commit, &["ABCDEF"]);
+
+        assert!(matches!(
+            result,
+            Err(GitError::SignatureVerificationFailed { reason, .. }) if reason == "no signature found"
+        ));
+    }
+
+    #[test]
+    fn test_signing_keys_gpg() {
+        let report = "[GNUPG:] GOODSIG 1234567890ABCDEF Test <test@example.com>\n\
+                      [GNUPG:] VALIDSIG AAAA1111BBBB2222CCCC3333DDDD4444EEEE5555 2024-01-01 1704067200 0 4 0 1 10 00 FFFF1111BBBB2222CCCC3333DDDD4444EEEE5555\n";
+
+        let keys = GitVerify::signing_keys(report);
+
+        assert_eq!(
+            keys,
+            vec![
+                "1234567890ABCDEF",
+                "AAAA1111BBBB2222CCCC3333DDDD4444EEEE5555",
+                "FFFF1111BBBB2222CCCC3333DDDD4444EEEE5555",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signing_keys_ssh() {
+        let report = "Good \"git\" signature for test@example.com with ED25519 key SHA256:abc123\n";
+
+        let keys = GitVerify::signing_keys(report);
+
+        assert_eq!(keys, vec!["SHA256:abc123"]);
+    }
+
+    #[test]
+    fn test_key_allowed() {
+        let fingerprint = "AAAA1111BBBB2222CCCC3333DDDD4444EEEE5555";
+
+        assert!(GitVerify::key_allowed(fingerprint, &["dddd4444eeee5555"]));
+        assert!(GitVerify::key_allowed(fingerprint, &[fingerprint]));
+        assert!(!GitVerify::key_allowed(fingerprint, &["1234"]));
+        assert!(!GitVerify::key_allowed(fingerprint, &["EEEE5555"]));
+        assert!(!GitVerify::key_allowed(fingerprint, &[""]));
+        assert!(GitVerify::key_allowed("SHA256:abc123", &["SHA256:abc123"]));
+        assert!(!GitVerify::key_allowed("SHA256:abc123", &["SHA256:ABC123"]));
+    }
+}